@@ -1,6 +1,16 @@
 extern crate gcc;
 use std::process::Command;
 use std::path::Path;
+use std::env;
+
+
+/// curves backed by `easy-ecc`, along with the static lib built for each.
+const CURVES: &[(&str,&str)] = &[
+    ("secp128r1","libp128.a"),
+    ("secp192r1","libp192.a"),
+    ("secp256r1","libp256.a"),
+    ("secp384r1","libp384.a"),
+];
 
 
 fn main() {
@@ -17,6 +27,26 @@ fn main() {
         }
     }
 
-    // compile `easy-ecc` into a static lib.
-    gcc::compile_library("libp256.a",&["dep/easy-ecc/ecc.c"]);
+    // compile `easy-ecc` once for each enabled curve, with its exported
+    // symbols prefixed so that the builds don't collide at link time.
+    for &(curve,lib) in CURVES {
+        let feature = format!("CARGO_FEATURE_{}",curve.to_uppercase());
+        if env::var_os(feature).is_some() {
+            compile_curve(curve,lib);
+        }
+    }
+}
+
+
+/// compile `easy-ecc` for `curve`, prefixing its symbols with the curve name.
+fn compile_curve(curve: &str, lib: &str) {
+    let mut config = gcc::Config::new();
+    config.file("dep/easy-ecc/ecc.c").define("ECC_CURVE",Some(curve));
+    // only `ecdsa_verify` is called, but every build exports all four
+    // symbols from the same object, so each must be prefixed to link more
+    // than one curve.
+    for symbol in &["ecc_make_key","ecdh_shared_secret","ecdsa_sign","ecdsa_verify"] {
+        config.define(symbol,Some(&format!("{}_{}",curve,symbol)));
+    }
+    config.compile(lib);
 }
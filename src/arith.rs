@@ -0,0 +1,630 @@
+//! portable multi-precision & elliptic curve arithmetic.
+//!
+//! `easy-ecc` keeps its point math private to `ecc.c`, so the handful of
+//! operations it doesn't expose are implemented here in terms of each
+//! curve's domain parameters.  integers are little-endian arrays of 64-bit
+//! limbs (the same layout `easy-ecc` uses for its curve constants), and
+//! field elements are held in montgomery form.
+
+
+/// maximum number of 64-bit limbs in any supported modulus.
+#[cfg(not(feature = "secp521r1"))]
+pub const MAX_LIMBS: usize = 6;
+
+/// maximum number of 64-bit limbs in any supported modulus.
+#[cfg(feature = "secp521r1")]
+pub const MAX_LIMBS: usize = 9;
+
+
+/// a little-endian multi-precision integer.
+pub type Limbs = [u64;MAX_LIMBS];
+
+
+/// load a big-endian byte string into limbs.
+pub fn from_be(bytes: &[u8]) -> Limbs {
+    debug_assert!(bytes.len() <= MAX_LIMBS * 8);
+    let mut limbs = [0u64;MAX_LIMBS];
+    for (i,byte) in bytes.iter().rev().enumerate() {
+        limbs[i / 8] |= (*byte as u64) << ((i % 8) * 8);
+    }
+    limbs
+}
+
+
+/// write limbs out as a big-endian byte string (truncating high bytes).
+pub fn to_be(limbs: &Limbs, bytes: &mut [u8]) {
+    debug_assert!(bytes.len() <= MAX_LIMBS * 8);
+    let len = bytes.len();
+    for (i,byte) in bytes.iter_mut().enumerate() {
+        let pos = len - 1 - i;
+        *byte = (limbs[pos / 8] >> ((pos % 8) * 8)) as u8;
+    }
+}
+
+
+/// clear limbs holding secret material (only with the `zeroize` feature).
+#[cfg(feature = "zeroize")]
+pub fn wipe(a: &mut Limbs) {
+    ::zeroize::Zeroize::zeroize(a);
+}
+
+/// clear limbs holding secret material (only with the `zeroize` feature).
+#[cfg(not(feature = "zeroize"))]
+pub fn wipe(_: &mut Limbs) { }
+
+
+/// clear bytes holding secret material (only with the `zeroize` feature).
+#[cfg(feature = "zeroize")]
+pub fn wipe_bytes(a: &mut [u8]) {
+    ::zeroize::Zeroize::zeroize(a);
+}
+
+/// clear bytes holding secret material (only with the `zeroize` feature).
+#[cfg(not(feature = "zeroize"))]
+pub fn wipe_bytes(_: &mut [u8]) { }
+
+
+/// check if an integer is zero, without branching on its value.
+pub fn is_zero(a: &Limbs) -> bool {
+    a.iter().fold(0, |acc,limb| acc | limb) == 0
+}
+
+
+/// compute `a - b` over the first `n` limbs, returning the final borrow.
+fn sub_borrow(a: &Limbs, b: &Limbs, n: usize, out: &mut Limbs) -> u64 {
+    let mut borrow = 0u64;
+    for i in 0..n {
+        let (d1,b1) = a[i].overflowing_sub(b[i]);
+        let (d2,b2) = d1.overflowing_sub(borrow);
+        out[i] = d2;
+        borrow = (b1 | b2) as u64;
+    }
+    borrow
+}
+
+
+/// compute `a + b` over the first `n` limbs, returning the final carry.
+fn add_carry(a: &Limbs, b: &Limbs, n: usize, out: &mut Limbs) -> u64 {
+    let mut carry = 0u64;
+    for i in 0..n {
+        let (s1,c1) = a[i].overflowing_add(b[i]);
+        let (s2,c2) = s1.overflowing_add(carry);
+        out[i] = s2;
+        carry = (c1 | c2) as u64;
+    }
+    carry
+}
+
+
+/// select `b` if `mask` is all ones, or `a` if it is all zeroes.
+pub fn select(a: &Limbs, b: &Limbs, mask: u64) -> Limbs {
+    let mut out = *a;
+    for (o,b) in out.iter_mut().zip(b.iter()) {
+        *o ^= mask & (*o ^ b);
+    }
+    out
+}
+
+
+/// check if `a == b`, without branching on where they differ.
+#[cfg(feature = "hazmat")]
+pub fn ct_equal(a: &Limbs, b: &Limbs) -> bool {
+    let mut diff = [0u64;MAX_LIMBS];
+    for (d,(a,b)) in diff.iter_mut().zip(a.iter().zip(b.iter())) {
+        *d = a ^ b;
+    }
+    is_zero(&diff)
+}
+
+
+/// compute `a + b` (without reduction), or `None` if it overflows.
+pub fn checked_add(a: &Limbs, b: &Limbs) -> Option<Limbs> {
+    let mut sum = [0u64;MAX_LIMBS];
+    if add_carry(a,b,MAX_LIMBS,&mut sum) == 0 { Some(sum) } else { None }
+}
+
+
+/// check if `a < b`.
+pub fn lt(a: &Limbs, b: &Limbs) -> bool {
+    let mut scratch = [0u64;MAX_LIMBS];
+    sub_borrow(a,b,MAX_LIMBS,&mut scratch) == 1
+}
+
+
+/// number of significant bits in `a`.
+pub fn bit_len(a: &Limbs) -> usize {
+    for i in (0..MAX_LIMBS).rev() {
+        if a[i] != 0 { return 64 * i + 64 - a[i].leading_zeros() as usize; }
+    }
+    0
+}
+
+
+/// interpret the leftmost `bits` bits of a big-endian string as an integer
+/// (the `bits2int` conversion of rfc6979 & sec1).
+pub fn bits2int(bytes: &[u8], bits: usize) -> Limbs {
+    let len = bits.div_ceil(8);
+    if bytes.len() <= len {
+        let shift = (bytes.len() * 8).saturating_sub(bits);
+        return shr(&from_be(bytes),shift);
+    }
+    shr(&from_be(&bytes[..len]),len * 8 - bits)
+}
+
+
+/// write a hash of any length to `out` (`bits` rounded up to whole bytes)
+/// such that `bits2int(out,bits)` is `bits2int(hash,bits)`, as fixed-size
+/// signing & verification expect.
+pub fn fit_hash(hash: &[u8], bits: usize, out: &mut [u8]) {
+    debug_assert!(out.len() == bits.div_ceil(8));
+    to_be(&bits2int(hash,bits),out);
+    let shift = out.len() * 8 - bits;
+    if shift != 0 {
+        for i in 0..out.len() {
+            out[i] = out[i] << shift | out.get(i + 1).map_or(0,|next| next >> (8 - shift));
+        }
+    }
+}
+
+
+/// shift `a` right by `bits` bits.
+fn shr(a: &Limbs, bits: usize) -> Limbs {
+    let (limbs, bits) = (bits / 64, bits % 64);
+    let mut out = [0u64;MAX_LIMBS];
+    for i in 0..(MAX_LIMBS - limbs.min(MAX_LIMBS)) {
+        out[i] = a[i+limbs] >> bits;
+        if bits != 0 && i + limbs + 1 < MAX_LIMBS { out[i] |= a[i+limbs+1] << (64 - bits); }
+    }
+    out
+}
+
+
+/// arithmetic modulo an odd integer, using montgomery multiplication.
+#[derive(Clone,Copy)]
+pub struct Modulus {
+    m: Limbs,
+    n: usize,
+    minv: u64,
+    one: Limbs,
+    r2: Limbs,
+}
+
+
+impl Modulus {
+
+    /// set up arithmetic modulo `m` (little-endian limbs).
+    pub fn new(m: &[u64]) -> Self {
+        debug_assert!(m.len() <= MAX_LIMBS && m[0] & 1 == 1);
+        let n = m.len();
+        let mut limbs = [0u64;MAX_LIMBS];
+        limbs[..n].copy_from_slice(m);
+        // newton iteration for `m^-1 mod 2^64`.
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m[0].wrapping_mul(inv)));
+        }
+        let mut modulus = Modulus { m: limbs, n, minv: inv.wrapping_neg(), one: [0;MAX_LIMBS], r2: [0;MAX_LIMBS] };
+        // `R = 2^(64n)` and `R^2`, by repeated doubling of one.
+        let mut acc = [0u64;MAX_LIMBS];
+        acc[0] = 1;
+        for _ in 0..(64 * n) { acc = modulus.add(&acc,&acc); }
+        modulus.one = acc;
+        for _ in 0..(64 * n) { acc = modulus.add(&acc,&acc); }
+        modulus.r2 = acc;
+        modulus
+    }
+
+    /// check if `a` is a fully reduced residue.
+    pub fn contains(&self, a: &Limbs) -> bool { lt(a,&self.m) }
+
+    /// `a + b mod m`.
+    pub fn add(&self, a: &Limbs, b: &Limbs) -> Limbs {
+        let (mut sum, mut diff) = ([0u64;MAX_LIMBS], [0u64;MAX_LIMBS]);
+        let carry = add_carry(a,b,self.n,&mut sum);
+        let borrow = sub_borrow(&sum,&self.m,self.n,&mut diff);
+        select(&sum,&diff,(carry | (borrow ^ 1)).wrapping_neg())
+    }
+
+    /// `a - b mod m`.
+    pub fn sub(&self, a: &Limbs, b: &Limbs) -> Limbs {
+        let (mut diff, mut wrapped) = ([0u64;MAX_LIMBS], [0u64;MAX_LIMBS]);
+        let borrow = sub_borrow(a,b,self.n,&mut diff);
+        add_carry(&diff,&self.m,self.n,&mut wrapped);
+        select(&diff,&wrapped,borrow.wrapping_neg())
+    }
+
+    /// montgomery product `a * b * R^-1 mod m`.
+    pub fn mul(&self, a: &Limbs, b: &Limbs) -> Limbs {
+        let n = self.n;
+        let mut t = [0u64;MAX_LIMBS+2];
+        for bi in b.iter().take(n) {
+            let mut carry = 0u64;
+            for j in 0..n {
+                let uv = t[j] as u128 + (a[j] as u128) * (*bi as u128) + carry as u128;
+                t[j] = uv as u64;
+                carry = (uv >> 64) as u64;
+            }
+            let uv = t[n] as u128 + carry as u128;
+            t[n] = uv as u64;
+            t[n+1] = (uv >> 64) as u64;
+            let q = t[0].wrapping_mul(self.minv);
+            let uv = t[0] as u128 + (q as u128) * (self.m[0] as u128);
+            let mut carry = (uv >> 64) as u64;
+            for j in 1..n {
+                let uv = t[j] as u128 + (q as u128) * (self.m[j] as u128) + carry as u128;
+                t[j-1] = uv as u64;
+                carry = (uv >> 64) as u64;
+            }
+            let uv = t[n] as u128 + carry as u128;
+            t[n-1] = uv as u64;
+            t[n] = t[n+1] + (uv >> 64) as u64;
+        }
+        let (mut lo, mut diff) = ([0u64;MAX_LIMBS], [0u64;MAX_LIMBS]);
+        lo[..n].copy_from_slice(&t[..n]);
+        let borrow = sub_borrow(&lo,&self.m,n,&mut diff);
+        select(&lo,&diff,(t[n] | (borrow ^ 1)).wrapping_neg())
+    }
+
+    /// number of bits in the modulus.
+    pub fn bits(&self) -> usize { bit_len(&self.m) }
+
+    /// reduce any value below `2^(64n)` into the range `[0,m)`.
+    pub fn reduce(&self, a: &Limbs) -> Limbs { self.canonical(&self.mont_form(a)) }
+
+    /// convert a reduced residue into montgomery form.
+    pub fn mont_form(&self, a: &Limbs) -> Limbs { self.mul(a,&self.r2) }
+
+    /// convert out of montgomery form.
+    pub fn canonical(&self, a: &Limbs) -> Limbs {
+        let mut unit = [0u64;MAX_LIMBS];
+        unit[0] = 1;
+        self.mul(a,&unit)
+    }
+
+    /// montgomery form of one.
+    pub fn one(&self) -> Limbs { self.one }
+
+    /// raise `a` (montgomery form) to a public exponent.
+    pub fn pow(&self, a: &Limbs, exp: &Limbs) -> Limbs {
+        let mut acc = self.one;
+        for i in (0..(64 * self.n)).rev() {
+            acc = self.mul(&acc,&acc);
+            if (exp[i / 64] >> (i % 64)) & 1 == 1 {
+                acc = self.mul(&acc,a);
+            }
+        }
+        acc
+    }
+
+    /// `a * b mod m`, for reduced residues out of montgomery form.
+    pub fn mul_reduced(&self, a: &Limbs, b: &Limbs) -> Limbs {
+        self.canonical(&self.mul(&self.mont_form(a),&self.mont_form(b)))
+    }
+
+    /// `-a mod m`.
+    #[cfg_attr(not(any(feature = "secp256k1", feature = "secp256r1")), allow(dead_code))]
+    pub fn neg(&self, a: &Limbs) -> Limbs { self.sub(&[0;MAX_LIMBS],a) }
+
+    /// multiplicative inverse of `a` (montgomery form), by fermat's little
+    /// theorem.  the modulus must be prime.
+    pub fn inv(&self, a: &Limbs) -> Limbs {
+        let mut two = [0u64;MAX_LIMBS];
+        two[0] = 2;
+        let mut exp = [0u64;MAX_LIMBS];
+        sub_borrow(&self.m,&two,self.n,&mut exp);
+        self.pow(a,&exp)
+    }
+
+    /// square root of `a` (montgomery form), if one exists.  the modulus must
+    /// be prime.
+    pub fn sqrt(&self, a: &Limbs) -> Option<Limbs> {
+        let mut unit = [0u64;MAX_LIMBS];
+        unit[0] = 1;
+        let root = if self.m[0] & 3 == 3 {
+            // `a^((m+1)/4)`.
+            let mut exp = [0u64;MAX_LIMBS];
+            add_carry(&self.m,&unit,self.n,&mut exp);
+            self.pow(a,&shr(&exp,2))
+        } else {
+            self.tonelli_shanks(a)
+        };
+        if self.mul(&root,&root) == *a { Some(root) } else { None }
+    }
+
+    /// tonelli-shanks square root, for primes congruent to 1 mod 4.  returns
+    /// garbage (which `sqrt` rejects) if `a` is a non-residue.  the running
+    /// time depends on `a`, which is fine for the public values this is used
+    /// on (point decompression).
+    fn tonelli_shanks(&self, a: &Limbs) -> Limbs {
+        let mut unit = [0u64;MAX_LIMBS];
+        unit[0] = 1;
+        // `m - 1 = q * 2^s` with `q` odd.
+        let mut pm1 = [0u64;MAX_LIMBS];
+        sub_borrow(&self.m,&unit,self.n,&mut pm1);
+        let s = pm1.iter().position(|limb| *limb != 0)
+            .map(|i| 64 * i + pm1[i].trailing_zeros() as usize)
+            .unwrap_or(0);
+        let q = shr(&pm1,s);
+        // smallest quadratic non-residue `z`, by euler's criterion.
+        let minus_one = self.sub(&[0;MAX_LIMBS],&self.one);
+        let mut z = self.one;
+        while self.pow(&z,&shr(&pm1,1)) != minus_one {
+            z = self.add(&z,&self.one);
+        }
+        let mut c = self.pow(&z,&q);
+        let mut exp = [0u64;MAX_LIMBS];
+        add_carry(&q,&unit,self.n,&mut exp);
+        let mut x = self.pow(a,&shr(&exp,1));
+        let mut t = self.pow(a,&q);
+        let mut m = s;
+        while t != self.one && !is_zero(&t) {
+            // least `i` with `t^(2^i) = 1`.
+            let (mut i, mut t2) = (0, t);
+            while t2 != self.one {
+                t2 = self.mul(&t2,&t2);
+                i += 1;
+                if i == m { return x; }
+            }
+            let mut b = c;
+            for _ in 0..(m - i - 1) { b = self.mul(&b,&b); }
+            x = self.mul(&x,&b);
+            c = self.mul(&b,&b);
+            t = self.mul(&t,&c);
+            m = i;
+        }
+        x
+    }
+}
+
+
+/// domain parameters of a short weierstrass curve `y^2 = x^3 + ax + b`.
+pub struct Params {
+    /// field prime.
+    pub p: &'static [u64],
+    /// curve coefficient `a`.
+    pub a: &'static [u64],
+    /// curve coefficient `b`.
+    pub b: &'static [u64],
+    /// base point x-coordinate.
+    pub gx: &'static [u64],
+    /// base point y-coordinate.
+    pub gy: &'static [u64],
+    /// order of the base point.
+    pub n: &'static [u64],
+}
+
+
+/// a point in projective coordinates (montgomery form), with the point at
+/// infinity represented as `(0 : 1 : 0)`.
+#[derive(Clone,Copy)]
+pub struct Point {
+    x: Limbs,
+    y: Limbs,
+    z: Limbs,
+}
+
+
+/// a curve with its field and scalar arithmetic set up.
+pub struct Curve {
+    /// arithmetic modulo the field prime.
+    pub fp: Modulus,
+    /// arithmetic modulo the group order.
+    pub fq: Modulus,
+    a: Limbs,
+    b: Limbs,
+    b3: Limbs,
+    g: Point,
+}
+
+
+impl Curve {
+
+    /// set up arithmetic for the given domain parameters.
+    pub fn new(params: &Params) -> Self {
+        let fp = Modulus::new(params.p);
+        let fq = Modulus::new(params.n);
+        let load = |limbs: &[u64]| {
+            let mut full = [0u64;MAX_LIMBS];
+            full[..limbs.len()].copy_from_slice(limbs);
+            fp.mont_form(&full)
+        };
+        let b = load(params.b);
+        let b3 = fp.add(&fp.add(&b,&b),&b);
+        let g = Point { x: load(params.gx), y: load(params.gy), z: fp.one() };
+        Curve { fp, fq, a: load(params.a), b, b3, g }
+    }
+
+    /// the base point.
+    pub fn generator(&self) -> Point { self.g }
+
+    /// the point at infinity.
+    pub fn identity(&self) -> Point {
+        Point { x: [0;MAX_LIMBS], y: self.fp.one(), z: [0;MAX_LIMBS] }
+    }
+
+    /// complete point addition (renes, costello & batina 2015, algorithm 1),
+    /// valid for all inputs including doubling and the point at infinity.
+    pub fn add(&self, p: &Point, q: &Point) -> Point {
+        let f = &self.fp;
+        let mut t0 = f.mul(&p.x,&q.x);
+        let mut t1 = f.mul(&p.y,&q.y);
+        let mut t2 = f.mul(&p.z,&q.z);
+        let mut t3 = f.add(&p.x,&p.y);
+        let mut t4 = f.add(&q.x,&q.y);
+        t3 = f.mul(&t3,&t4);
+        t4 = f.add(&t0,&t1);
+        t3 = f.sub(&t3,&t4);
+        t4 = f.add(&p.x,&p.z);
+        let mut t5 = f.add(&q.x,&q.z);
+        t4 = f.mul(&t4,&t5);
+        t5 = f.add(&t0,&t2);
+        t4 = f.sub(&t4,&t5);
+        t5 = f.add(&p.y,&p.z);
+        let mut x3 = f.add(&q.y,&q.z);
+        t5 = f.mul(&t5,&x3);
+        x3 = f.add(&t1,&t2);
+        t5 = f.sub(&t5,&x3);
+        let mut z3 = f.mul(&self.a,&t4);
+        x3 = f.mul(&self.b3,&t2);
+        z3 = f.add(&x3,&z3);
+        x3 = f.sub(&t1,&z3);
+        z3 = f.add(&t1,&z3);
+        let mut y3 = f.mul(&x3,&z3);
+        t1 = f.add(&t0,&t0);
+        t1 = f.add(&t1,&t0);
+        t2 = f.mul(&self.a,&t2);
+        t4 = f.mul(&self.b3,&t4);
+        t1 = f.add(&t1,&t2);
+        t2 = f.sub(&t0,&t2);
+        t2 = f.mul(&self.a,&t2);
+        t4 = f.add(&t4,&t2);
+        t0 = f.mul(&t1,&t4);
+        y3 = f.add(&y3,&t0);
+        t0 = f.mul(&t5,&t4);
+        x3 = f.mul(&x3,&t3);
+        x3 = f.sub(&x3,&t0);
+        t0 = f.mul(&t3,&t1);
+        z3 = f.mul(&z3,&t5);
+        z3 = f.add(&z3,&t0);
+        Point { x: x3, y: y3, z: z3 }
+    }
+
+    /// the negation of a point.
+    #[cfg(feature = "hazmat")]
+    pub fn neg(&self, p: &Point) -> Point {
+        Point { x: p.x, y: self.fp.neg(&p.y), z: p.z }
+    }
+
+    /// check if a point is the point at infinity.
+    #[cfg(feature = "hazmat")]
+    pub fn is_identity(&self, p: &Point) -> bool { is_zero(&p.z) }
+
+    /// scalar multiplication by a montgomery ladder over every bit of `k`
+    /// (so the sequence of operations doesn't depend on its value).
+    pub fn mul(&self, p: &Point, k: &Limbs) -> Point {
+        let mut r0 = self.identity();
+        let mut r1 = *p;
+        for i in (0..(64 * MAX_LIMBS)).rev() {
+            let mask = ((k[i / 64] >> (i % 64)) & 1).wrapping_neg();
+            cswap(&mut r0,&mut r1,mask);
+            r1 = self.add(&r0,&r1);
+            r0 = self.add(&r0,&r0);
+            cswap(&mut r0,&mut r1,mask);
+        }
+        r0
+    }
+
+    /// normalize to affine `(x,y)` (out of montgomery form), or `None` for
+    /// the point at infinity.
+    pub fn to_affine(&self, p: &Point) -> Option<(Limbs,Limbs)> {
+        if is_zero(&p.z) { return None; }
+        let zinv = self.fp.inv(&p.z);
+        let x = self.fp.canonical(&self.fp.mul(&p.x,&zinv));
+        let y = self.fp.canonical(&self.fp.mul(&p.y,&zinv));
+        Some((x,y))
+    }
+
+    /// check if two points are equal.
+    #[cfg_attr(not(any(feature = "secp256k1", feature = "hazmat")), allow(dead_code))]
+    pub fn equal(&self, p: &Point, q: &Point) -> bool {
+        self.to_affine(p) == self.to_affine(q)
+    }
+
+    /// check that `k` is a valid secret scalar (`0 < k < n`).
+    pub fn is_scalar(&self, k: &Limbs) -> bool {
+        !is_zero(k) && self.fq.contains(k)
+    }
+
+    /// check if the scalar `s` exceeds `n/2` (the "high" half of the
+    /// range, which low-s normalization excludes).
+    pub fn is_high(&self, s: &Limbs) -> bool {
+        lt(&shr(&self.fq.m,1),s)
+    }
+
+    /// parse a point in sec1 compressed form (`bytes + 1` octets), checking
+    /// that it lies on the curve.
+    pub fn decompress(&self, bytes: &[u8]) -> Option<Point> {
+        if bytes[0] != 0x02 && bytes[0] != 0x03 { return None; }
+        self.lift_x(&from_be(&bytes[1..]),bytes[0] & 1 == 1)
+    }
+
+    /// the curve point with x-coordinate `x` (an integer, which must be
+    /// less than `p`) & a y-coordinate of the given parity, if any.
+    pub fn lift_x(&self, x: &Limbs, odd: bool) -> Option<Point> {
+        let f = &self.fp;
+        if !f.contains(x) { return None; }
+        let x = f.mont_form(x);
+        // `y^2 = x^3 + ax + b`.
+        let rhs = f.add(&f.mul(&f.add(&f.mul(&x,&x),&self.a),&x),&self.b);
+        let mut y = f.sqrt(&rhs)?;
+        if (f.canonical(&y)[0] & 1 == 1) != odd {
+            y = f.sub(&[0;MAX_LIMBS],&y);
+        }
+        Some(Point { x, y, z: f.one() })
+    }
+
+    /// the group order `n`.
+    pub fn order(&self) -> Limbs { self.fq.m }
+
+    /// the coefficients `a` & `b` of `y^2 = x^3 + ax + b` (montgomery form).
+    #[cfg(feature = "secp256r1")]
+    pub fn coefficients(&self) -> (Limbs,Limbs) { (self.a, self.b) }
+
+    /// the point with affine coordinates `x` & `y` (montgomery form), if it
+    /// lies on the curve.
+    pub fn affine_point(&self, x: &Limbs, y: &Limbs) -> Option<Point> {
+        let f = &self.fp;
+        let rhs = f.add(&f.mul(&f.add(&f.mul(x,x),&self.a),x),&self.b);
+        if f.mul(y,y) != rhs { return None; }
+        Some(Point { x: *x, y: *y, z: f.one() })
+    }
+
+    /// parse a point in sec1 uncompressed form (`2 * bytes + 1` octets),
+    /// checking that it lies on the curve.
+    pub fn decode_uncompressed(&self, bytes: &[u8]) -> Option<Point> {
+        let f = &self.fp;
+        if bytes[0] != 0x04 || bytes.len() % 2 != 1 { return None; }
+        let len = bytes.len() / 2;
+        let (x, y) = (from_be(&bytes[1..1+len]), from_be(&bytes[1+len..]));
+        if !f.contains(&x) || !f.contains(&y) { return None; }
+        self.affine_point(&f.mont_form(&x),&f.mont_form(&y))
+    }
+
+    /// write out a point in sec1 uncompressed form (`2 * bytes + 1` octets).
+    pub fn encode_uncompressed(&self, p: &Point, out: &mut [u8]) -> bool {
+        let len = out.len() / 2;
+        match self.to_affine(p) {
+            Some((x,y)) => {
+                out[0] = 0x04;
+                to_be(&x,&mut out[1..1+len]);
+                to_be(&y,&mut out[1+len..]);
+                true
+            },
+            None => false
+        }
+    }
+
+    /// write out a point in sec1 compressed form (`bytes + 1` octets).
+    pub fn compress(&self, p: &Point, out: &mut [u8]) -> bool {
+        match self.to_affine(p) {
+            Some((x,y)) => {
+                out[0] = 0x02 | (y[0] & 1) as u8;
+                to_be(&x,&mut out[1..]);
+                true
+            },
+            None => false
+        }
+    }
+}
+
+
+/// swap `a` & `b` if `mask` is all ones.
+fn cswap(a: &mut Point, b: &mut Point, mask: u64) {
+    let (x0,y0,z0) = (a.x,a.y,a.z);
+    a.x = select(&a.x,&b.x,mask);
+    a.y = select(&a.y,&b.y,mask);
+    a.z = select(&a.z,&b.z,mask);
+    b.x = select(&b.x,&x0,mask);
+    b.y = select(&b.y,&y0,mask);
+    b.z = select(&b.z,&z0,mask);
+}
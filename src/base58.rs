@@ -0,0 +1,129 @@
+//! base58 & base58check coding, in the bitcoin alphabet (used by bip-32
+//! extended keys & multibase).
+use sha2::{Sha256,Digest};
+use error::Error;
+
+
+/// the bitcoin alphabet.
+const ALPHABET: &[u8;58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// size of the checksum appended to the payload.
+#[cfg_attr(not(feature = "secp256k1"), allow(dead_code))]
+const CHECKSUM_SIZE: usize = 4;
+
+
+/// encode `payload`, followed by its checksum (the first four bytes of its
+/// double sha-256 hash).
+#[cfg_attr(not(feature = "secp256k1"), allow(dead_code))]
+pub fn encode_check(payload: &[u8]) -> String {
+    let mut bytes = payload.to_vec();
+    bytes.extend_from_slice(&checksum(payload));
+    encode(&bytes)
+}
+
+
+/// decode `text`, checking & stripping its checksum.  a character outside
+/// the alphabet, or a bad checksum, yields `Error::MalformedEncoding`.
+#[cfg_attr(not(feature = "secp256k1"), allow(dead_code))]
+pub fn decode_check(text: &str) -> Result<Vec<u8>,Error> {
+    let bytes = decode(text)?;
+    if bytes.len() < CHECKSUM_SIZE { return Err(Error::MalformedEncoding); }
+    let (payload, check) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+    if checksum(payload) != check { return Err(Error::MalformedEncoding); }
+    Ok(payload.to_vec())
+}
+
+
+/// encode `bytes` without a checksum (as multibase's `base58btc` does).
+pub fn encode(bytes: &[u8]) -> String {
+    // each leading zero byte is written as a leading `1`.
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for byte in &bytes[zeros..] {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let mut out = String::with_capacity(zeros + digits.len());
+    for _ in 0..zeros { out.push('1'); }
+    for digit in digits.iter().rev() { out.push(ALPHABET[*digit as usize] as char); }
+    out
+}
+
+
+/// decode `text`, which has no checksum.  a character outside the alphabet
+/// yields `Error::MalformedEncoding`.
+pub fn decode(text: &str) -> Result<Vec<u8>,Error> {
+    let zeros = text.bytes().take_while(|c| *c == b'1').count();
+    let mut bytes: Vec<u8> = Vec::with_capacity(text.len());
+    for c in text.bytes().skip(zeros) {
+        let mut carry = ALPHABET.iter().position(|a| *a == c).ok_or(Error::MalformedEncoding)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes.resize(bytes.len() + zeros,0);
+    bytes.reverse();
+    Ok(bytes)
+}
+
+
+/// the checksum of `payload`.
+#[cfg_attr(not(feature = "secp256k1"), allow(dead_code))]
+fn checksum(payload: &[u8]) -> [u8;CHECKSUM_SIZE] {
+    let hash = Sha256::digest(Sha256::digest(payload));
+    let mut out = [0u8;CHECKSUM_SIZE];
+    out.copy_from_slice(&hash[..CHECKSUM_SIZE]);
+    out
+}
+
+
+#[cfg(test)]
+mod tests {
+    use base58::{encode,decode,encode_check,decode_check};
+    use error::Error;
+
+    #[test]
+    fn base58_ok() {
+        // a version-zero payload (as in a bitcoin address), & one with
+        // several leading zeros.
+        let payload = [0x00, 0x01, 0x09, 0x66, 0x77, 0x60, 0x06, 0x95, 0x3d, 0x55, 0x67, 0x43, 0x9e, 0x5e, 0x39, 0xf8, 0x6a, 0x0d, 0x27, 0x3b, 0xee];
+        assert_eq!(encode_check(&payload),"16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM");
+        assert_eq!(decode_check("16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM").unwrap(),&payload[..]);
+        for payload in &[&[][..],&[0,0,0][..],&[0xff;40][..]] {
+            assert_eq!(decode_check(&encode_check(payload)).unwrap(),*payload);
+        }
+    }
+
+    #[test]
+    fn base58_raw_ok() {
+        // from the base58 draft (draft-msporny-base58).
+        assert_eq!(encode(b"Hello World!"),"2NEpo7TZRRrLZSi2U");
+        assert_eq!(encode(&[0x00,0x00,0x28,0x7f,0xb4,0xcd]),"11233QC4");
+        assert_eq!(decode("11233QC4").unwrap(),[0x00,0x00,0x28,0x7f,0xb4,0xcd]);
+        assert_eq!(decode("").unwrap(),[]);
+        assert_eq!(decode("0").err(),Some(Error::MalformedEncoding));
+    }
+
+    #[test]
+    fn base58_err() {
+        // a changed digit, characters outside the alphabet, & input too
+        // short to hold a checksum.
+        for text in &["16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvN","16UwLL9Risc3QfPqBUvKofHmBQ7wMtjv0","1I","","11"] {
+            assert_eq!(decode_check(text).err(),Some(Error::MalformedEncoding));
+        }
+    }
+}
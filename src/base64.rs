@@ -0,0 +1,138 @@
+//! base64 coding (rfc4648), in the standard & url-safe alphabets.
+use error::Error;
+
+
+/// the standard alphabet (used by pem & openssh).
+#[cfg_attr(not(any(feature = "pem", feature = "encoding", feature = "secp256r1", feature = "secp384r1", feature = "secp521r1")), allow(dead_code))]
+pub const STANDARD: &[u8;64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// the url-safe alphabet (used, without padding, by jose).
+pub const URL_SAFE: &[u8;64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+
+/// a base64 variant for the `to_base64` & `from_base64` methods of the key
+/// & signature types.
+#[cfg(feature = "encoding")]
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Base64 {
+    /// the standard alphabet, padded (as in pem).
+    Standard,
+    /// the standard alphabet, without padding.
+    StandardUnpadded,
+    /// the url-safe alphabet, padded.
+    UrlSafe,
+    /// the url-safe alphabet, without padding (as in jose).
+    UrlSafeUnpadded,
+}
+
+#[cfg(feature = "encoding")]
+impl Base64 {
+    /// encode `bytes` in this variant.
+    pub(crate) fn encode(self, bytes: &[u8]) -> String {
+        let (alphabet, pad) = self.params();
+        encode(bytes,alphabet,pad)
+    }
+
+    /// decode `text` in this variant into `out`, which it must fill exactly
+    /// (or `Error::WrongLength` results).
+    pub(crate) fn decode(self, text: &str, out: &mut [u8]) -> Result<(),Error> {
+        let (alphabet, pad) = self.params();
+        let mut bytes = decode(text,alphabet,pad)?;
+        let result = if bytes.len() == out.len() {
+            out.copy_from_slice(&bytes);
+            Ok(())
+        } else {
+            Err(Error::WrongLength { expected: out.len(), got: bytes.len() })
+        };
+        ::arith::wipe_bytes(&mut bytes);
+        result
+    }
+
+    /// the alphabet, & whether output is padded.
+    fn params(self) -> (&'static [u8;64], bool) {
+        match self {
+            Base64::Standard => (STANDARD,true),
+            Base64::StandardUnpadded => (STANDARD,false),
+            Base64::UrlSafe => (URL_SAFE,true),
+            Base64::UrlSafeUnpadded => (URL_SAFE,false),
+        }
+    }
+}
+
+
+/// encode `bytes`, padding the output to a multiple of four characters if
+/// `pad` is set.
+pub fn encode(bytes: &[u8], alphabet: &[u8;64], pad: bool) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut block = [0u8;3];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let bits = (block[0] as u32) << 16 | (block[1] as u32) << 8 | block[2] as u32;
+        for i in 0..(chunk.len() + 1) {
+            out.push(alphabet[(bits >> (18 - 6 * i)) as usize & 0x3f] as char);
+        }
+        if pad {
+            for _ in chunk.len()..3 { out.push('='); }
+        }
+    }
+    out
+}
+
+
+/// decode `text`, which must be padded exactly if `pad` is set (& not at all
+/// otherwise).  any non-canonical input yields `Error::MalformedEncoding`.
+pub fn decode(text: &str, alphabet: &[u8;64], pad: bool) -> Result<Vec<u8>,Error> {
+    let mut text = text.as_bytes();
+    if pad {
+        if !text.len().is_multiple_of(4) { return Err(Error::MalformedEncoding); }
+        let padding = text.iter().rev().take(2).take_while(|c| **c == b'=').count();
+        text = &text[..text.len()-padding];
+    }
+    if text.len() % 4 == 1 { return Err(Error::MalformedEncoding); }
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.chunks(4) {
+        let mut bits = 0u32;
+        for c in chunk {
+            let value = alphabet.iter().position(|a| a == c).ok_or(Error::MalformedEncoding)?;
+            bits = bits << 6 | value as u32;
+        }
+        bits <<= 6 * (4 - chunk.len()) as u32;
+        let bytes = [(bits >> 16) as u8, (bits >> 8) as u8, bits as u8];
+        let len = chunk.len() * 6 / 8;
+        // the bits below the last whole byte must be zero.
+        if bytes[len..].iter().any(|byte| *byte != 0) { return Err(Error::MalformedEncoding); }
+        out.extend_from_slice(&bytes[..len]);
+    }
+    Ok(out)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use base64::{encode,decode,STANDARD,URL_SAFE};
+    use error::Error;
+
+    #[test]
+    fn standard_ok() {
+        for &(raw,encoded) in &[("",""),("f","Zg=="),("fo","Zm8="),("foo","Zm9v"),("foobar","Zm9vYmFy")] {
+            assert_eq!(encode(raw.as_bytes(),STANDARD,true),encoded);
+            assert_eq!(decode(encoded,STANDARD,true).unwrap(),raw.as_bytes());
+        }
+    }
+
+    #[test]
+    fn url_safe_ok() {
+        assert_eq!(encode(&[0xfb,0xff],URL_SAFE,false),"-_8");
+        assert_eq!(decode("-_8",URL_SAFE,false).unwrap(),vec![0xfb,0xff]);
+    }
+
+    #[test]
+    fn decode_err() {
+        for bad in &["Zg=","Zh==","Zg==Zg==","Z===","Zm9*"] {
+            assert_eq!(decode(bad,STANDARD,true),Err(Error::MalformedEncoding));
+        }
+        for bad in &["Zg==","Z","-_8="] {
+            assert_eq!(decode(bad,URL_SAFE,false),Err(Error::MalformedEncoding));
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! bech32 coding (bip-173) of byte strings under a caller-chosen
+//! human-readable part.  only built with the `encoding` feature.
+//!
+//! bip-173's limit of 90 characters isn't applied, since it was set for
+//! segwit addresses & keys on the larger curves don't fit it.
+use error::Error;
+
+
+/// the data alphabet.
+const CHARSET: &[u8;32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// generator of the checksum's bch code.
+const GENERATOR: [u32;5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+/// number of characters in the checksum.
+const CHECKSUM_SIZE: usize = 6;
+
+/// maximum length of the human-readable part.
+const MAX_HRP_SIZE: usize = 83;
+
+
+/// encode `data` under the human-readable part `hrp`, which must be 1 to 83
+/// printable ascii characters (or `Error::MalformedEncoding` results).
+/// the output is lowercase.
+pub fn encode(hrp: &str, data: &[u8]) -> Result<String,Error> {
+    let hrp = check_hrp(hrp)?;
+    let mut values = Vec::with_capacity((data.len() * 8).div_ceil(5) + CHECKSUM_SIZE);
+    let (mut acc, mut bits) = (0u32, 0);
+    for byte in data {
+        acc = acc << 8 | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            values.push((acc >> bits) as u8 & 0x1f);
+        }
+    }
+    if bits > 0 { values.push((acc << (5 - bits)) as u8 & 0x1f); }
+    let check = polymod(&hrp,&values,true) ^ 1;
+    for i in 0..CHECKSUM_SIZE {
+        values.push((check >> (5 * (CHECKSUM_SIZE - 1 - i))) as u8 & 0x1f);
+    }
+    let mut out = String::with_capacity(hrp.len() + 1 + values.len());
+    out.push_str(&hrp);
+    out.push('1');
+    for value in values { out.push(CHARSET[value as usize] as char); }
+    Ok(out)
+}
+
+
+/// decode `text`, which must carry the human-readable part `hrp` (compared
+/// ignoring case) or `Error::InvalidKey` results.  mixed case, characters
+/// outside the alphabet, a bad checksum, or data which isn't a whole
+/// number of bytes (with zero padding) yields `Error::MalformedEncoding`.
+pub fn decode(hrp: &str, text: &str) -> Result<Vec<u8>,Error> {
+    let expected = check_hrp(hrp)?;
+    if text.bytes().any(|c| c.is_ascii_lowercase()) && text.bytes().any(|c| c.is_ascii_uppercase()) {
+        return Err(Error::MalformedEncoding);
+    }
+    let text = text.to_ascii_lowercase();
+    let split = text.rfind('1').ok_or(Error::MalformedEncoding)?;
+    let (found, data) = (check_hrp(&text[..split])?, &text[split+1..]);
+    if data.len() < CHECKSUM_SIZE { return Err(Error::MalformedEncoding); }
+    let values = data.bytes()
+        .map(|c| CHARSET.iter().position(|a| *a == c).map(|value| value as u8).ok_or(Error::MalformedEncoding))
+        .collect::<Result<Vec<u8>,Error>>()?;
+    if polymod(&found,&values,false) != 1 { return Err(Error::MalformedEncoding); }
+    if found != expected { return Err(Error::InvalidKey); }
+    let values = &values[..values.len()-CHECKSUM_SIZE];
+    let mut out = Vec::with_capacity(values.len() * 5 / 8);
+    let (mut acc, mut bits) = (0u32, 0);
+    for value in values {
+        acc = (acc << 5 | *value as u32) & 0xfff;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    // at most four bits of padding, all zero.
+    if bits >= 5 || acc & ((1 << bits) - 1) != 0 { return Err(Error::MalformedEncoding); }
+    Ok(out)
+}
+
+
+/// check a human-readable part, returning it in lowercase.
+fn check_hrp(hrp: &str) -> Result<String,Error> {
+    if hrp.is_empty() || hrp.len() > MAX_HRP_SIZE || hrp.bytes().any(|c| !(33..=126).contains(&c)) {
+        return Err(Error::MalformedEncoding);
+    }
+    Ok(hrp.to_ascii_lowercase())
+}
+
+
+/// the checksum's bch code over the expanded `hrp` & `values`, followed by
+/// a blank checksum if `blank` is set.
+fn polymod(hrp: &str, values: &[u8], blank: bool) -> u32 {
+    let expanded = hrp.bytes().map(|c| c >> 5)
+        .chain(Some(0))
+        .chain(hrp.bytes().map(|c| c & 0x1f))
+        .chain(values.iter().cloned())
+        .chain([0u8;CHECKSUM_SIZE].iter().cloned().take(if blank { CHECKSUM_SIZE } else { 0 }));
+    let mut check = 1u32;
+    for value in expanded {
+        let top = check >> 25;
+        check = (check & 0x1ffffff) << 5 ^ value as u32;
+        for (i,generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 { check ^= generator; }
+        }
+    }
+    check
+}
+
+
+#[cfg(test)]
+mod tests {
+    use bech32::{encode,decode};
+    use error::Error;
+
+    #[test]
+    fn bech32_ok() {
+        // a nostr public key (nip-19), & the empty string from bip-173.
+        let key = [0x3b,0xf0,0xc6,0x3f,0xcb,0x93,0x46,0x34,0x07,0xaf,0x97,0xa5,0xe5,0xee,0x64,0xfa,0x88,0x3d,0x10,0x7e,0xf9,0xe5,0x58,0x47,0x2c,0x4e,0xb9,0xaa,0xae,0xfa,0x45,0x9d];
+        let text = "npub180cvv07tjdrrgpa0j7j7tmnyl2yr6yr7l8j4s3evf6u64th6gkwsyjh6w6";
+        assert_eq!(encode("npub",&key).unwrap(),text);
+        assert_eq!(decode("npub",text).unwrap(),key);
+        assert_eq!(decode("NPUB",&text.to_uppercase()).unwrap(),key);
+        assert_eq!(decode("a","A12UEL5L").unwrap(),[]);
+        for len in 0..12 {
+            let data: Vec<u8> = (0..len).map(|i| 0xf0 ^ i as u8).collect();
+            assert_eq!(decode("x",&encode("x",&data).unwrap()).unwrap(),data);
+        }
+    }
+
+    #[test]
+    fn bech32_err() {
+        let text = encode("key",&[0xaa;4]).unwrap();
+        assert_eq!(decode("other",&text),Err(Error::InvalidKey));
+        // a changed character, mixed case, a character outside the
+        // alphabet, a missing separator, & an empty or invalid hrp.
+        let changed = [&text[..5],if &text[5..6] == "q" { "p" } else { "q" },&text[6..]].concat();
+        assert_eq!(decode("key",&changed),Err(Error::MalformedEncoding));
+        assert_eq!(decode("key",&text.replacen("k","K",1)),Err(Error::MalformedEncoding));
+        assert_eq!(decode("key",&[&text[..4],"b",&text[5..]].concat()),Err(Error::MalformedEncoding));
+        assert_eq!(decode("key","keyqqqqqq"),Err(Error::MalformedEncoding));
+        assert_eq!(encode("",&[]),Err(Error::MalformedEncoding));
+        assert_eq!(encode("a b",&[]),Err(Error::MalformedEncoding));
+        // bip-173's invalid strings: an empty hrp, & a truncated checksum.
+        assert_eq!(decode("a","1pzry9x0s0muk"),Err(Error::MalformedEncoding));
+        assert_eq!(decode("li","li1dgmt3"),Err(Error::MalformedEncoding));
+        // five bits of padding, & padding which isn't zero.
+        assert_eq!(decode("x","x1qm7ssx4"),Err(Error::MalformedEncoding));
+        assert_eq!(decode("x","x1llssn9at"),Err(Error::MalformedEncoding));
+    }
+}
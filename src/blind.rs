@@ -0,0 +1,202 @@
+//! blind schnorr signatures over `secp256k1`, producing ordinary bip-340
+//! signatures which verify against the signer's x-only key with
+//! `schnorr::Public::verify`.
+//!
+//! the signer sends a nonce commitment from `commit`; the requester blinds
+//! its message against it with `Blinder::new` & sends back the blinded
+//! challenge; the signer answers with `sign`; & the requester unblinds the
+//! answer into a signature the signer has never seen, over a message it has
+//! never seen.
+//!
+//! the signer must finish each session before starting the next: with many
+//! sessions open at once, a requester can combine their answers into one
+//! more signature than were issued (the ros attack of benhamouda et al.).
+//!
+//! round messages are plain serializable structs, but a `SignerNonce` is the
+//! secret nonce `k` behind a `NonceCommitment`, & `sign` consumes it: two
+//! answers under one `k` differ by the difference of their challenges times
+//! the secret key, which a requester can then solve for.  it can be neither
+//! cloned nor serialized, so a nonce answers exactly one challenge.
+use arith::{self,Curve,Limbs,MAX_LIMBS};
+use secp256k1::{self,PARAMS};
+use schnorr;
+use error::Error;
+use random;
+
+
+/// size of curve.
+const BYTES: usize = 32;
+
+
+/// the signer's secret nonce for one session.  it's consumed by `sign` (&
+/// wiped on drop), since answering two challenges with the same nonce would
+/// leak the secret key.
+pub struct SignerNonce {
+    k: Limbs,
+}
+
+impl Drop for SignerNonce {
+    fn drop(&mut self) { arith::wipe(&mut self.k); }
+}
+
+
+/// the signer's first message: the nonce point `R = kG`.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct NonceCommitment {
+    /// the nonce point.
+    pub r: secp256k1::Public,
+}
+
+
+/// the requester's message: the blinded challenge scalar.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct BlindedChallenge {
+    /// the big-endian challenge scalar.
+    pub e: [u8;BYTES],
+}
+
+
+/// the signer's answer: the blinded response scalar.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct BlindSignature {
+    /// the big-endian response scalar.
+    pub s: [u8;BYTES],
+}
+
+
+/// begin a session as the signer: draw a fresh nonce, returning it along
+/// with the commitment to send to the requester.
+pub fn commit() -> Result<(SignerNonce,NonceCommitment),Error> {
+    let curve = Curve::new(&PARAMS);
+    let nonce = SignerNonce { k: random::scalar(&curve.fq)? };
+    let r = schnorr::encode_point(&curve,&curve.mul(&curve.generator(),&nonce.k))?;
+    Ok((nonce,NonceCommitment { r }))
+}
+
+
+/// answer the requester's blinded challenge with `secret`, consuming the
+/// session's nonce.
+pub fn sign(nonce: SignerNonce, secret: &schnorr::Secret, challenge: &BlindedChallenge) -> Result<BlindSignature,Error> {
+    let curve = Curve::new(&PARAMS);
+    let fq = &curve.fq;
+    let e = arith::from_be(&challenge.e);
+    if !fq.contains(&e) {
+        return Err(Error::MalformedSignature);
+    }
+    let mut d = arith::from_be(secret.as_ref());
+    if !curve.is_scalar(&d) {
+        return Err(Error::InvalidSecretKey);
+    }
+    // sign with the scalar of the even-y point, as bip-340 requires.
+    let (_,y) = curve.to_affine(&curve.mul(&curve.generator(),&d)).ok_or(Error::InvalidSecretKey)?;
+    if y[0] & 1 == 1 { d = fq.neg(&d); }
+    let mut ed = fq.mul_reduced(&e,&d);
+    let s = fq.add(&nonce.k,&ed);
+    let mut sig = BlindSignature { s: [0u8;BYTES] };
+    arith::to_be(&s,&mut sig.s);
+    arith::wipe(&mut d);
+    arith::wipe(&mut ed);
+    Ok(sig)
+}
+
+
+/// the requester's state during a session: the blinding factor & the
+/// unblinded signature's nonce (wiped on drop).
+pub struct Blinder {
+    public: schnorr::Public,
+    msg: Vec<u8>,
+    alpha: Limbs,
+    rx: [u8;BYTES],
+}
+
+impl Blinder {
+    /// blind `msg` against the signer's nonce commitment, for a signature
+    /// under `public`.  returns the state to keep & the challenge to send to
+    /// the signer.  a key or commitment which isn't a valid curve point
+    /// yields `Error::InvalidKey`.
+    pub fn new(public: &schnorr::Public, commitment: &NonceCommitment, msg: &[u8]) -> Result<(Blinder,BlindedChallenge),Error> {
+        let curve = Curve::new(&PARAMS);
+        let fq = &curve.fq;
+        let p = curve.lift_x(&arith::from_be(public.as_ref()),false).ok_or(Error::InvalidKey)?;
+        let r = schnorr::decode_point(&curve,&commitment.r)?;
+        let mut blinder = Blinder { public: *public, msg: msg.to_vec(), alpha: [0;MAX_LIMBS], rx: [0u8;BYTES] };
+        // `R' = R + alpha G + beta P`, redrawn until it has even y.
+        for _ in 0..random::MAX_TRIES {
+            blinder.alpha = random::scalar(fq)?;
+            let mut beta = random::scalar(fq)?;
+            let blinded = curve.add(&r,&curve.add(&curve.mul(&curve.generator(),&blinder.alpha),&curve.mul(&p,&beta)));
+            if let Some((x,y)) = curve.to_affine(&blinded) {
+                if y[0] & 1 == 0 {
+                    arith::to_be(&x,&mut blinder.rx);
+                    let e = fq.add(&schnorr::challenge(&curve,&blinder.rx,public.as_ref(),msg),&beta);
+                    arith::wipe(&mut beta);
+                    let mut challenge = BlindedChallenge { e: [0u8;BYTES] };
+                    arith::to_be(&e,&mut challenge.e);
+                    return Ok((blinder,challenge));
+                }
+            }
+            arith::wipe(&mut beta);
+        }
+        Err(Error::RetriesExhausted)
+    }
+
+    /// unblind the signer's answer into a bip-340 signature over the
+    /// message, which is verified before being returned.  an answer which
+    /// doesn't yield a valid signature gives `Error::InvalidSignature`.
+    pub fn unblind(self, answer: &BlindSignature) -> Result<schnorr::Signature,Error> {
+        let fq = Curve::new(&PARAMS).fq;
+        let s = arith::from_be(&answer.s);
+        if !fq.contains(&s) {
+            return Err(Error::MalformedSignature);
+        }
+        let mut raw = [0u8;BYTES*2];
+        raw[..BYTES].copy_from_slice(&self.rx);
+        arith::to_be(&fq.add(&s,&self.alpha),&mut raw[BYTES..]);
+        let sig = schnorr::Signature::from(raw);
+        self.public.verify(&self.msg,&sig)?;
+        Ok(sig)
+    }
+}
+
+impl Drop for Blinder {
+    fn drop(&mut self) { arith::wipe(&mut self.alpha); }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use blind::{BlindSignature,Blinder,commit,sign};
+    use schnorr::KeyPair;
+    use error::Error;
+
+    #[test]
+    fn blind_ok() {
+        let pair = KeyPair::generate().unwrap();
+        for msg in &[&b"token"[..],&b"another token"[..]] {
+            let (nonce, commitment) = commit().unwrap();
+            let (blinder, challenge) = Blinder::new(pair.public(),&commitment,msg).unwrap();
+            let answer = sign(nonce,pair.secret(),&challenge).unwrap();
+            let sig = blinder.unblind(&answer).unwrap();
+            pair.public().verify(msg,&sig).unwrap();
+            // the signer never saw the signature's nonce.
+            assert!(sig.as_ref()[..32] != commitment.r.as_ref()[1..]);
+        }
+    }
+
+    #[test]
+    fn blind_err() {
+        let pair = KeyPair::generate().unwrap();
+        let other = KeyPair::generate().unwrap();
+        // an answer from the wrong key doesn't unblind.
+        let (nonce, commitment) = commit().unwrap();
+        let (blinder, challenge) = Blinder::new(pair.public(),&commitment,b"token").unwrap();
+        let answer = sign(nonce,other.secret(),&challenge).unwrap();
+        assert_eq!(blinder.unblind(&answer).err(),Some(Error::InvalidSignature));
+        let (_, commitment) = commit().unwrap();
+        let (blinder, _) = Blinder::new(pair.public(),&commitment,b"token").unwrap();
+        assert_eq!(blinder.unblind(&BlindSignature { s: [0xff;32] }).err(),Some(Error::MalformedSignature));
+    }
+}
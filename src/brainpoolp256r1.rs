@@ -0,0 +1,117 @@
+//! types and functions for signing operations on the `brainpoolP256r1` curve
+//! of rfc5639.
+//!
+//! only built with the `brainpoolp256r1` feature.  `easy-ecc` doesn't
+//! implement the brainpool curves, so this module is backed by the crate's
+//! portable arithmetic.
+use arith::Params;
+use error::Error;
+use portable;
+
+
+/// size of curve.
+const BYTES: usize = 32;
+
+
+/// domain parameters of `brainpoolP256r1`.
+const PARAMS: Params = Params {
+    p: &[0x2013481D1F6E5377, 0x6E3BF623D5262028, 0x3E660A909D838D72, 0xA9FB57DBA1EEA9BC],
+    a: &[0xE94A4B44F330B5D9, 0xFB8055C126DC5C6C, 0xEEF67530417AFFE7, 0x7D5A0975FC2C3057],
+    b: &[0x6BCCDC18FF8C07B6, 0x958416295CF7E1CE, 0xF330B5D9BBD77CBF, 0x26DC5C6CE94A4B44],
+    gx: &[0x3A4453BD9ACE3262, 0xB9DE27E1E3BD23C2, 0x2C4B482FFC81B7AF, 0x8BD2AEB9CB7E57CB],
+    gy: &[0x5C1D54C72F046997, 0xC27745132DED8E54, 0x97F8461A14611DC9, 0x547EF835C3DAC4FD],
+    n: &[0x901E0E82974856A7, 0x8C397AA3B561A6F7, 0x3E660A909D838D71, 0xA9FB57DBA1EEA9BC],
+};
+
+
+/// der encoding of the curve's object identifier (`1.3.36.3.3.2.8.1.1.7`).
+const OID: &[u8] = &[0x2b, 0x24, 0x03, 0x03, 0x02, 0x08, 0x01, 0x01, 0x07];
+
+
+/// curve name used in json web keys.  no name is registered with iana for
+/// this curve, so the rfc5639 name is used.
+const JWK_CRV: &str = "brainpoolP256r1";
+
+
+impl_curve!(BrainpoolP256r1,"brainpoolP256r1",::sha2::Sha256);
+
+
+// backend functions for `impl_curve!`, using the portable arithmetic.
+fn backend_keygen(public: &mut Public, secret: &mut Secret) -> Result<(),Error> {
+    portable::keygen(&PARAMS,&mut public.0,&mut secret.0)
+}
+
+
+fn backend_sign(key: &Secret, msg: &[u8;BYTES]) -> Result<Signature,Error> {
+    let mut sig = Signature::default();
+    portable::sign(&PARAMS,&key.0,msg,&mut sig.0)?;
+    Ok(sig)
+}
+
+
+fn backend_verify(key: &Public, msg: &[u8;BYTES], sig: &Signature) -> Result<(),Error> {
+    portable::verify(&PARAMS,&key.0,msg,&sig.0)
+}
+
+
+fn backend_ecdh(public: &Public, secret: &Secret) -> Result<SharedSecret,Error> {
+    let mut shared = SharedSecret::default();
+    portable::ecdh(&PARAMS,&public.0,&secret.0,&mut shared.0)?;
+    Ok(shared)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use brainpoolp256r1::{BYTES,Prehash,Public,Secret,Signature,KeyPair,verify,ecdh};
+    use error::Error;
+
+    #[test]
+    #[allow(deprecated)]
+    fn keygen_ok() {
+        use brainpoolp256r1::keygen;
+        let mut public = Public::default();
+        let mut secret = Secret::default();
+        keygen(&mut public, &mut secret).unwrap();
+
+        assert!(public != Public::default());
+        assert!(secret != Secret::default());
+        assert!(secret.public().unwrap() == public);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn signing_ok() {
+        use brainpoolp256r1::sign;
+        let pair = KeyPair::generate().unwrap();
+        let mut sig = Signature::default();
+        let mut msg = [0u8;BYTES];
+        msg[0] = 1; msg[2] = 3; msg[4] = 5;
+        let hash = Prehash::from_hash(&msg).unwrap();
+        sign(pair.secret(),&msg,&mut sig).unwrap();
+        verify(pair.public(),&hash,&sig).unwrap();
+        msg[0] ^= 0xff;
+        assert_eq!(verify(pair.public(),&Prehash::from_hash(&msg).unwrap(),&sig),Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn ecdh_ok() {
+        let (pair_a, pair_b) = (KeyPair::generate().unwrap(), KeyPair::generate().unwrap());
+        let shared_a = ecdh(pair_b.public(),pair_a.secret()).unwrap();
+        let shared_b = ecdh(pair_a.public(),pair_b.secret()).unwrap();
+        assert_eq!(shared_a,shared_b);
+    }
+
+    #[test]
+    fn generator_ok() {
+        let mut one = [0u8;BYTES];
+        one[BYTES-1] = 1;
+        assert!(Secret::from(one).public().unwrap() == Public::from([
+            0x03, 0x8b, 0xd2, 0xae, 0xb9, 0xcb, 0x7e, 0x57,
+            0xcb, 0x2c, 0x4b, 0x48, 0x2f, 0xfc, 0x81, 0xb7,
+            0xaf, 0xb9, 0xde, 0x27, 0xe1, 0xe3, 0xbd, 0x23,
+            0xc2, 0x3a, 0x44, 0x53, 0xbd, 0x9a, 0xce, 0x32,
+            0x62
+        ]));
+    }
+}
@@ -0,0 +1,236 @@
+//! an encrypted, authenticated pipe between two `secp256r1` identities.
+//! only built with the `secp256r1` feature.
+//!
+//! each side draws a `Handshake` & sends its public key to the other along
+//! with its identity key; `Handshake::finish` then combines three ecdh
+//! exchanges (ephemeral-ephemeral, & each identity with the other side's
+//! ephemeral key) through hkdf into one aes-256-gcm key per direction.  a
+//! party who doesn't hold the identity secret it claims arrives at
+//! different keys, so its first message fails to decrypt.
+//!
+//! messages must be delivered in order: each direction numbers its
+//! messages with an implicit 64-bit counter used as the gcm nonce, so a
+//! dropped, replayed or reordered message fails with
+//! `Error::DecryptionFailed` (without advancing the counter).  `rekey`
+//! ratchets a direction's key forward, & must be called at the same point
+//! on both ends; it also happens automatically before the counter wraps.
+use aes_gcm::{Aes256Gcm,KeyInit,Nonce};
+use aes_gcm::aead::Aead;
+use secp256r1::{Public,KeyPair,ecdh};
+use error::Error;
+use arith;
+use hkdf;
+
+
+/// size of a direction's key.
+const KEY_LEN: usize = hkdf::KEY_LEN;
+
+/// size of an aes-gcm authentication tag, added to every message.
+pub const TAG_LEN: usize = 16;
+
+/// hkdf `info` prefix for the directional keys.
+const INFO: &[u8] = b"easy-ecc/channel/v1";
+
+/// hkdf `info` for ratcheting a key forward.
+const REKEY_INFO: &[u8] = b"easy-ecc/channel/rekey";
+
+
+/// one side's fresh ephemeral key for establishing a channel.
+pub struct Handshake {
+    ephemeral: KeyPair,
+}
+
+impl Handshake {
+    /// draw a new ephemeral key.
+    pub fn new() -> Result<Self,Error> {
+        Ok(Handshake { ephemeral: KeyPair::generate()? })
+    }
+
+    /// the ephemeral public key to send to the other side.
+    pub fn public(&self) -> &Public { self.ephemeral.public() }
+
+    /// derive the channel as `identity`, given the other side's identity &
+    /// ephemeral keys, consuming the ephemeral key.  returns the halves
+    /// for sending & receiving.  a remote identity equal to our own yields
+    /// `Error::InvalidKey`.
+    pub fn finish(self, identity: &KeyPair, remote_identity: &Public, remote_ephemeral: &Public) -> Result<(Encryptor,Decryptor),Error> {
+        let local = identity.public().as_ref();
+        let remote = remote_identity.as_ref();
+        if local == remote {
+            return Err(Error::InvalidKey);
+        }
+        // order the exchanges (& keys) by identity, so that both sides agree.
+        let low = local < remote;
+        let ee = ecdh(remote_ephemeral,self.ephemeral.secret())?;
+        let se = ecdh(remote_ephemeral,identity.secret())?;
+        let es = ecdh(remote_identity,self.ephemeral.secret())?;
+        let (first, second) = if low { (se, es) } else { (es, se) };
+        let mut ikm = [ee.expose_secret().as_ref(),first.expose_secret(),second.expose_secret()].concat();
+        let (ids, ephemerals) = if low {
+            ([local,remote], [self.ephemeral.public().as_ref(),remote_ephemeral.as_ref()])
+        } else {
+            ([remote,local], [remote_ephemeral.as_ref(),self.ephemeral.public().as_ref()])
+        };
+        let info = [INFO,ids[0],ids[1],ephemerals[0],ephemerals[1]].concat();
+        let mut keys = [0u8;KEY_LEN*2];
+        let rslt = hkdf::derive(&[],&ikm,&info,&mut keys);
+        arith::wipe_bytes(&mut ikm);
+        rslt?;
+        // the first key carries messages from the lower identity.
+        let (send, recv) = if low { keys.split_at(KEY_LEN) } else { let (a, b) = keys.split_at(KEY_LEN); (b, a) };
+        let channel = (Encryptor { state: State::new(send) }, Decryptor { state: State::new(recv) });
+        arith::wipe_bytes(&mut keys);
+        Ok(channel)
+    }
+}
+
+
+/// the key & message counter of one direction.
+struct State {
+    key: [u8;KEY_LEN],
+    counter: u64,
+}
+
+impl State {
+    fn new(key: &[u8]) -> Self {
+        let mut state = State { key: [0u8;KEY_LEN], counter: 0 };
+        state.key.copy_from_slice(key);
+        state
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new_from_slice(&self.key).expect("aes-256 keys are 32 bytes")
+    }
+
+    fn nonce(&self) -> [u8;12] {
+        let mut nonce = [0u8;12];
+        nonce[4..].copy_from_slice(&self.counter.to_be_bytes());
+        nonce
+    }
+
+    /// move on to the next message, rekeying before the counter wraps.
+    fn advance(&mut self) {
+        if self.counter == u64::MAX { self.rekey(); } else { self.counter += 1; }
+    }
+
+    fn rekey(&mut self) {
+        let mut key = [0u8;KEY_LEN];
+        hkdf::derive(&[],&self.key,REKEY_INFO,&mut key).expect("a key is within hkdf's output limit");
+        self.key = key;
+        self.counter = 0;
+        arith::wipe_bytes(&mut key);
+    }
+}
+
+impl Drop for State {
+    fn drop(&mut self) { arith::wipe_bytes(&mut self.key); }
+}
+
+
+/// the sending half of a channel.
+pub struct Encryptor {
+    state: State,
+}
+
+impl Encryptor {
+    /// encrypt the next message, which grows by `TAG_LEN` bytes.
+    pub fn seal(&mut self, plaintext: &[u8]) -> Vec<u8> {
+        let sealed = self.state.cipher().encrypt(Nonce::from_slice(&self.state.nonce()),plaintext)
+            .expect("aes-gcm accepts plaintexts up to 64 gib");
+        self.state.advance();
+        sealed
+    }
+
+    /// ratchet the key forward, so that earlier keys can't be recovered
+    /// from this one.  the receiver must `rekey` after the same message.
+    pub fn rekey(&mut self) { self.state.rekey(); }
+}
+
+
+/// the receiving half of a channel.
+pub struct Decryptor {
+    state: State,
+}
+
+impl Decryptor {
+    /// decrypt the next message.  a message which was tampered with, or
+    /// isn't the next one sent, yields `Error::DecryptionFailed`.
+    pub fn open(&mut self, sealed: &[u8]) -> Result<Vec<u8>,Error> {
+        let plaintext = self.state.cipher().decrypt(Nonce::from_slice(&self.state.nonce()),sealed)
+            .map_err(|_| Error::DecryptionFailed)?;
+        self.state.advance();
+        Ok(plaintext)
+    }
+
+    /// ratchet the key forward, as the sender did with `Encryptor::rekey`.
+    pub fn rekey(&mut self) { self.state.rekey(); }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use channel::{Handshake,Encryptor,Decryptor,TAG_LEN};
+    use secp256r1::KeyPair;
+    use error::Error;
+
+    fn pipe(alice: &KeyPair, bob: &KeyPair) -> ((Encryptor,Decryptor),(Encryptor,Decryptor)) {
+        let (a, b) = (Handshake::new().unwrap(), Handshake::new().unwrap());
+        let (a_eph, b_eph) = (*a.public(), *b.public());
+        (a.finish(alice,bob.public(),&b_eph).unwrap(), b.finish(bob,alice.public(),&a_eph).unwrap())
+    }
+
+    #[test]
+    fn channel_ok() {
+        let (alice, bob) = (KeyPair::generate().unwrap(), KeyPair::generate().unwrap());
+        let ((mut a_send, mut a_recv), (mut b_send, mut b_recv)) = pipe(&alice,&bob);
+        for msg in &[&b"hello"[..],&b""[..],&b"hello"[..]] {
+            let sealed = a_send.seal(msg);
+            assert_eq!(sealed.len(),msg.len() + TAG_LEN);
+            assert_eq!(b_recv.open(&sealed).unwrap(),msg.to_vec());
+            assert_eq!(a_recv.open(&b_send.seal(msg)).unwrap(),msg.to_vec());
+        }
+        // repeated messages don't repeat ciphertexts (& the receiver falls
+        // behind, until both ends rekey).
+        assert!(a_send.seal(b"hello") != a_send.seal(b"hello"));
+        a_send.rekey();
+        b_recv.rekey();
+        assert_eq!(b_recv.open(&a_send.seal(b"after rekey")).unwrap(),b"after rekey".to_vec());
+    }
+
+    #[test]
+    fn channel_err() {
+        let (alice, bob, mallory) = (KeyPair::generate().unwrap(), KeyPair::generate().unwrap(), KeyPair::generate().unwrap());
+        let ((mut a_send, _), (_, mut b_recv)) = pipe(&alice,&bob);
+        let (first, second) = (a_send.seal(b"first"), a_send.seal(b"second"));
+        // out of order, then tampered: neither advances the counter.
+        assert_eq!(b_recv.open(&second),Err(Error::DecryptionFailed));
+        let mut tampered = first.clone();
+        tampered[0] ^= 0x01;
+        assert_eq!(b_recv.open(&tampered),Err(Error::DecryptionFailed));
+        assert_eq!(b_recv.open(&first).unwrap(),b"first".to_vec());
+        assert_eq!(b_recv.open(&first),Err(Error::DecryptionFailed));
+        assert_eq!(b_recv.open(&second).unwrap(),b"second".to_vec());
+        // a missed rekey desynchronizes the channel.
+        a_send.rekey();
+        assert_eq!(b_recv.open(&a_send.seal(b"third")),Err(Error::DecryptionFailed));
+        // mallory claiming alice's identity can't talk to bob.
+        let (m, b) = (Handshake::new().unwrap(), Handshake::new().unwrap());
+        let (m_eph, b_eph) = (*m.public(), *b.public());
+        let (mut m_send, _) = m.finish(&mallory,bob.public(),&b_eph).unwrap();
+        let (_, mut b_recv) = b.finish(&bob,alice.public(),&m_eph).unwrap();
+        assert_eq!(b_recv.open(&m_send.seal(b"hi bob")),Err(Error::DecryptionFailed));
+        let handshake = Handshake::new().unwrap();
+        assert_eq!(handshake.finish(&alice,alice.public(),&b_eph).err(),Some(Error::InvalidKey));
+    }
+
+    #[test]
+    fn counter_wrap() {
+        let (alice, bob) = (KeyPair::generate().unwrap(), KeyPair::generate().unwrap());
+        let ((mut send, _), (_, mut recv)) = pipe(&alice,&bob);
+        send.state.counter = u64::MAX;
+        recv.state.counter = u64::MAX;
+        assert_eq!(recv.open(&send.seal(b"last")).unwrap(),b"last".to_vec());
+        assert_eq!(send.state.counter,0);
+        assert_eq!(recv.open(&send.seal(b"rekeyed")).unwrap(),b"rekeyed".to_vec());
+    }
+}
@@ -0,0 +1,219 @@
+//! cose keys (rfc9052 §7 & rfc9053 §7.1.1) holding ec2 public keys, as used
+//! by webauthn & cbor-based iot protocols.  keys are written in
+//! deterministic cbor (rfc8949 §4.2) with just the key type, curve & both
+//! coordinates; other members (such as `kid` or `alg`) are skipped when
+//! reading, as is a secret scalar.
+use error::Error;
+
+
+/// label of the key type.
+const KTY: i64 = 1;
+
+/// label of the curve.
+const CRV: i64 = -1;
+
+/// label of the x-coordinate.
+const X: i64 = -2;
+
+/// label of the y-coordinate (or its sign bit).
+const Y: i64 = -3;
+
+/// the ec2 key type.
+const EC2: i64 = 2;
+
+/// nesting depth beyond which skipped items are rejected.
+const MAX_DEPTH: usize = 16;
+
+
+/// encode an ec2 key on the curve with cose id `crv`.
+pub fn encode(crv: i64, x: &[u8], y: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(x.len() + y.len() + 16);
+    write_head(5,4,&mut out);
+    write_int(KTY,&mut out);
+    write_int(EC2,&mut out);
+    write_int(CRV,&mut out);
+    write_int(crv,&mut out);
+    write_int(X,&mut out);
+    write_bytes(x,&mut out);
+    write_int(Y,&mut out);
+    write_bytes(y,&mut out);
+    out
+}
+
+
+/// decode an ec2 key on the curve with cose id `crv` & coordinates of
+/// `size` bytes, returning its sec1 point (uncompressed, or compressed
+/// where the key gives only the sign of `y`).  keys of another type or
+/// curve yield `Error::InvalidKey`, while malformed cbor, a missing member
+/// or a coordinate of the wrong size yields `Error::MalformedEncoding`.
+pub fn decode(crv: i64, size: usize, bytes: &[u8]) -> Result<Vec<u8>,Error> {
+    let mut buf = bytes;
+    let (major, len) = read_head(&mut buf)?;
+    if major != 5 { return Err(Error::MalformedEncoding); }
+    let (mut kty, mut curve, mut x, mut y) = (None, None, None, None);
+    for _ in 0..len {
+        let (major, arg) = read_head(&mut buf)?;
+        let label = match major {
+            0 => arg as i64,
+            1 => -1 - arg as i64,
+            // text labels are never ours.
+            3 => { take(&mut buf,arg)?; skip(&mut buf,0)?; continue; },
+            _ => return Err(Error::MalformedEncoding),
+        };
+        let slot = match label {
+            KTY => &mut kty,
+            CRV => &mut curve,
+            X => &mut x,
+            Y => &mut y,
+            _ => { skip(&mut buf,0)?; continue; },
+        };
+        if slot.is_some() { return Err(Error::MalformedEncoding); }
+        *slot = Some(read_value(&mut buf)?);
+    }
+    if !buf.is_empty() { return Err(Error::MalformedEncoding); }
+    if kty != Some(Value::Int(EC2)) || curve != Some(Value::Int(crv)) {
+        return Err(Error::InvalidKey);
+    }
+    // checking each coordinate, as a mis-split pair concatenates to the
+    // same point.
+    match (x, y) {
+        (Some(Value::Bytes(x)), Some(Value::Bytes(y))) if x.len() == size && y.len() == size => Ok([&[0x04],x,y].concat()),
+        (Some(Value::Bytes(x)), Some(Value::Bool(odd))) if x.len() == size => Ok([&[0x02 | odd as u8],x].concat()),
+        _ => Err(Error::MalformedEncoding),
+    }
+}
+
+
+/// a member value we care about.
+#[derive(Debug,PartialEq,Eq)]
+enum Value<'a> {
+    Int(i64),
+    Bytes(&'a [u8]),
+    Bool(bool),
+}
+
+
+/// read an integer, byte string or boolean.
+fn read_value<'a>(buf: &mut &'a [u8]) -> Result<Value<'a>,Error> {
+    match read_head(buf)? {
+        (0, arg) => Ok(Value::Int(arg as i64)),
+        (1, arg) => Ok(Value::Int(-1 - arg as i64)),
+        (2, len) => take(buf,len).map(Value::Bytes),
+        (7, 20) => Ok(Value::Bool(false)),
+        (7, 21) => Ok(Value::Bool(true)),
+        _ => Err(Error::MalformedEncoding),
+    }
+}
+
+
+/// skip one item of any type, nested no deeper than `MAX_DEPTH`.
+fn skip(buf: &mut &[u8], depth: usize) -> Result<(),Error> {
+    if depth > MAX_DEPTH { return Err(Error::MalformedEncoding); }
+    match read_head(buf)? {
+        (0, _) | (1, _) | (7, _) => Ok(()),
+        (2, len) | (3, len) => take(buf,len).map(|_| ()),
+        (4, len) => (0..len).try_for_each(|_| skip(buf,depth + 1)),
+        (5, len) => (0..len).try_for_each(|_| { skip(buf,depth + 1)?; skip(buf,depth + 1) }),
+        (6, _) => skip(buf,depth + 1),
+        _ => Err(Error::MalformedEncoding),
+    }
+}
+
+
+/// read an item's major type & argument.  indefinite lengths & floats
+/// aren't supported.
+fn read_head(buf: &mut &[u8]) -> Result<(u8,u64),Error> {
+    let initial = take(buf,1)?[0];
+    let (major, info) = (initial >> 5, initial & 0x1f);
+    let arg = match info {
+        0..=23 => info as u64,
+        24..=27 => take(buf,1 << (info - 24))?.iter().fold(0,|arg,byte| arg << 8 | *byte as u64),
+        _ => return Err(Error::MalformedEncoding),
+    };
+    if major == 7 && info > 23 { return Err(Error::MalformedEncoding); }
+    if (major == 0 || major == 1) && arg > i64::MAX as u64 { return Err(Error::MalformedEncoding); }
+    Ok((major, arg))
+}
+
+
+/// take `len` bytes from the front of `buf`.
+fn take<'a>(buf: &mut &'a [u8], len: u64) -> Result<&'a [u8],Error> {
+    if (buf.len() as u64) < len { return Err(Error::MalformedEncoding); }
+    let (front, rest) = buf.split_at(len as usize);
+    *buf = rest;
+    Ok(front)
+}
+
+
+/// append an item head in its shortest form.
+fn write_head(major: u8, arg: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    match arg {
+        0..=23 => out.push(major | arg as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, arg as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(arg as u16).to_be_bytes());
+        },
+        _ => {
+            out.push(major | 26);
+            out.extend_from_slice(&(arg as u32).to_be_bytes());
+        },
+    }
+}
+
+
+/// append a (small) integer.
+fn write_int(n: i64, out: &mut Vec<u8>) {
+    if n < 0 { write_head(1,(-1 - n) as u64,out) } else { write_head(0,n as u64,out) }
+}
+
+
+/// append a byte string.
+fn write_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    write_head(2,bytes.len() as u64,out);
+    out.extend_from_slice(bytes);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use cose::{encode,decode};
+    use error::Error;
+
+    #[test]
+    fn cose_ok() {
+        let key = encode(1,&[0xaa;2],&[0xbb;2]);
+        assert_eq!(key,[0xa4,0x01,0x02,0x20,0x01,0x21,0x42,0xaa,0xaa,0x22,0x42,0xbb,0xbb]);
+        assert_eq!(decode(1,2,&key).unwrap(),[0x04,0xaa,0xaa,0xbb,0xbb]);
+        // members in another order, other members (a text label, `kid`,
+        // `alg` & nested `key_ops`), & a sign bit in place of `y`.
+        let key = [0xa8,0x22,0xf5,0x63,b'f',b'o',b'o',0x80,0x21,0x41,0xaa,0x02,0x41,0x01,0x04,0x81,0x02,0x01,0x02,0x20,0x01,0x03,0x26];
+        assert_eq!(decode(1,1,&key).unwrap(),[0x03,0xaa]);
+    }
+
+    #[test]
+    fn cose_err() {
+        let key = encode(1,&[0xaa;2],&[0xbb;2]);
+        // the wrong curve or key type.
+        assert_eq!(decode(2,2,&key),Err(Error::InvalidKey));
+        let mut okp = key.clone();
+        okp[2] = 0x01;
+        assert_eq!(decode(1,2,&okp),Err(Error::InvalidKey));
+        // truncated, trailing data, a duplicate member, a missing member, &
+        // not a map.
+        assert_eq!(decode(1,2,&key[..key.len()-1]),Err(Error::MalformedEncoding));
+        assert_eq!(decode(1,2,&[&key[..],&[0x00]].concat()),Err(Error::MalformedEncoding));
+        let duplicate = [&[0xa5][..],&key[1..],&[0x22,0x41,0xbb]].concat();
+        assert_eq!(decode(1,2,&duplicate),Err(Error::MalformedEncoding));
+        assert_eq!(decode(1,2,&[0xa3,0x01,0x02,0x20,0x01,0x21,0x41,0xaa]),Err(Error::MalformedEncoding));
+        assert_eq!(decode(1,2,&[0x80]),Err(Error::MalformedEncoding));
+        // coordinates split in the wrong place, & a sign bit with an `x`
+        // of the wrong size.
+        assert_eq!(decode(1,2,&encode(1,&[0xaa;1],&[0xbb;3])),Err(Error::MalformedEncoding));
+        assert_eq!(decode(1,2,&encode(1,&[0xaa;3],&[0xbb;1])),Err(Error::MalformedEncoding));
+        assert_eq!(decode(1,2,&[0xa4,0x01,0x02,0x20,0x01,0x21,0x41,0xaa,0x22,0xf5]),Err(Error::MalformedEncoding));
+        // indefinite lengths aren't supported.
+        assert_eq!(decode(1,2,&[0xbf,0xff]),Err(Error::MalformedEncoding));
+    }
+}
@@ -0,0 +1,98 @@
+//! a common interface over the curve modules, for code which is generic over
+//! the choice of curve.
+use error::Error;
+
+
+/// an elliptic curve supported by this crate.  each curve module exports a
+/// marker type implementing this trait (e.g. `secp256k1::Secp256k1`).
+pub trait Curve {
+    /// public key type.
+    type Public;
+
+    /// secret key type.
+    type Secret;
+
+    /// signature type.
+    type Signature;
+
+    /// message hash type (the curve module's `Prehash`).
+    type Message;
+
+    /// size of a serialized (compressed) public key.
+    const PUBLIC_KEY_SIZE: usize;
+
+    /// size of a serialized secret key.
+    const SECRET_KEY_SIZE: usize;
+
+    /// size of a serialized signature.
+    const SIGNATURE_SIZE: usize;
+
+    /// generate a new ecc keypair.
+    fn keygen() -> Result<(Self::Public,Self::Secret),Error>;
+
+    /// generate a new ecc signature.
+    fn sign(key: &Self::Secret, msg: &Self::Message) -> Result<Self::Signature,Error>;
+
+    /// verify an ecc signature.
+    fn verify(key: &Self::Public, msg: &Self::Message, sig: &Self::Signature) -> Result<(),Error>;
+}
+
+
+/// the parity of a point's y-coordinate, which together with its
+/// x-coordinate picks out the point (as the sec1 prefix byte `0x02` or
+/// `0x03` does).
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Parity {
+    /// an even y-coordinate.
+    Even,
+    /// an odd y-coordinate.
+    Odd,
+}
+
+
+/// generate a new ecc keypair on curve `C`.
+pub fn keygen<C: Curve>() -> Result<(C::Public,C::Secret),Error> {
+    C::keygen()
+}
+
+
+/// generate a new ecc signature on curve `C`.
+pub fn sign<C: Curve>(key: &C::Secret, msg: &C::Message) -> Result<C::Signature,Error> {
+    C::sign(key,msg)
+}
+
+
+/// verify an ecc signature on curve `C`.
+pub fn verify<C: Curve>(key: &C::Public, msg: &C::Message, sig: &C::Signature) -> Result<(),Error> {
+    C::verify(key,msg,sig)
+}
+
+
+#[cfg(all(test, feature = "secp224r1", feature = "secp256k1"))]
+mod tests {
+    use curve::{Curve,keygen,sign,verify};
+    use secp224r1::{self,Secp224r1};
+    use secp256k1::{self,Secp256k1};
+    use error::Error;
+
+    fn roundtrip<C: Curve>(msg: C::Message, other: C::Message) {
+        let (public, secret) = keygen::<C>().unwrap();
+        let sig = sign::<C>(&secret,&msg).unwrap();
+        verify::<C>(&public,&msg,&sig).unwrap();
+        assert_eq!(verify::<C>(&public,&other,&sig),Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn generic_ok() {
+        roundtrip::<Secp224r1>(secp224r1::Prehash::from_sha256(b"msg"),secp224r1::Prehash::from_sha256(b"other"));
+        roundtrip::<Secp256k1>(secp256k1::Prehash::from_sha256(b"msg"),secp256k1::Prehash::from_sha256(b"other"));
+    }
+
+    #[test]
+    fn sizes_ok() {
+        assert_eq!(Secp256k1::PUBLIC_KEY_SIZE,33);
+        assert_eq!(Secp256k1::SECRET_KEY_SIZE,32);
+        assert_eq!(Secp256k1::SIGNATURE_SIZE,64);
+        assert_eq!(Secp224r1::SIGNATURE_SIZE,56);
+    }
+}
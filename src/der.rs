@@ -0,0 +1,297 @@
+//! minimal der encoding & decoding, covering the handful of asn.1
+//! structures used for key & signature interchange.
+use error::Error;
+
+
+/// tag of an asn.1 `INTEGER`.
+pub const INTEGER: u8 = 0x02;
+
+/// tag of an asn.1 `BIT STRING`.
+pub const BIT_STRING: u8 = 0x03;
+
+/// tag of an asn.1 `OCTET STRING`.
+pub const OCTET_STRING: u8 = 0x04;
+
+/// tag of an asn.1 `OBJECT IDENTIFIER`.
+pub const OBJECT_IDENTIFIER: u8 = 0x06;
+
+/// tag of an asn.1 `SEQUENCE`.
+pub const SEQUENCE: u8 = 0x30;
+
+
+/// der encoding of `id-ecPublicKey` (`1.2.840.10045.2.1`).
+pub const EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+
+
+/// tag of an explicit context-specific field `[n]`.
+pub fn context(n: u8) -> u8 { 0xa0 | n }
+
+
+/// append a tag-length-value triple to `out`.
+pub fn write(tag: u8, content: &[u8], out: &mut Vec<u8>) {
+    out.push(tag);
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = (len as u64).to_be_bytes();
+        let skip = bytes.iter().take_while(|byte| **byte == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(content);
+}
+
+
+/// encode a tag-length-value triple.
+pub fn encode(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(content.len() + 4);
+    write(tag,content,&mut out);
+    out
+}
+
+
+/// sequential reader over der-encoded values.  every malformation
+/// (including non-minimal lengths) yields `Error::MalformedEncoding`.
+pub struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+
+impl<'a> Reader<'a> {
+
+    /// read from the start of `buf`.
+    pub fn new(buf: &'a [u8]) -> Self { Reader { buf } }
+
+    /// check if all input has been consumed.
+    pub fn is_empty(&self) -> bool { self.buf.is_empty() }
+
+    /// tag of the next value, if any.
+    pub fn peek(&self) -> Option<u8> { self.buf.first().cloned() }
+
+    /// read the next value, which must have tag `tag`, returning its content.
+    pub fn read(&mut self, tag: u8) -> Result<&'a [u8],Error> {
+        if self.buf.len() < 2 || self.buf[0] != tag {
+            return Err(Error::MalformedEncoding);
+        }
+        let (len, header) = match self.buf[1] {
+            short if short < 0x80 => (short as usize, 2),
+            long => {
+                let count = (long & 0x7f) as usize;
+                if count == 0 || count > 4 || self.buf.len() < 2 + count || self.buf[2] == 0 {
+                    return Err(Error::MalformedEncoding);
+                }
+                let len = self.buf[2..2+count].iter().fold(0usize, |acc,byte| (acc << 8) | *byte as usize);
+                if len < 0x80 { return Err(Error::MalformedEncoding); }
+                (len, 2 + count)
+            }
+        };
+        if self.buf.len() - header < len {
+            return Err(Error::MalformedEncoding);
+        }
+        let content = &self.buf[header..header+len];
+        self.buf = &self.buf[header+len..];
+        Ok(content)
+    }
+
+    /// read the next value if it has tag `tag`.
+    pub fn read_optional(&mut self, tag: u8) -> Result<Option<&'a [u8]>,Error> {
+        if self.peek() == Some(tag) { self.read(tag).map(Some) } else { Ok(None) }
+    }
+
+    /// check that all input has been consumed.
+    pub fn finish(&self) -> Result<(),Error> {
+        if self.is_empty() { Ok(()) } else { Err(Error::MalformedEncoding) }
+    }
+}
+
+
+/// read a `BIT STRING`, which must contain a whole number of bytes.
+pub fn read_bit_string<'a>(reader: &mut Reader<'a>) -> Result<&'a [u8],Error> {
+    match reader.read(BIT_STRING)? {
+        content if !content.is_empty() && content[0] == 0 => Ok(&content[1..]),
+        _ => Err(Error::MalformedEncoding),
+    }
+}
+
+
+/// the parts of a sec1 `ECPrivateKey` (rfc5915).
+pub struct EcPrivateKey<'a> {
+    /// the big-endian secret scalar.
+    pub secret: &'a [u8],
+    /// the der-encoded curve identifier, if present.
+    pub curve: Option<&'a [u8]>,
+    /// the sec1-encoded public point, if present.
+    pub public: Option<&'a [u8]>,
+}
+
+
+impl<'a> EcPrivateKey<'a> {
+
+    /// encode as der.
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut body = encode(INTEGER,&[0x01]);
+        write(OCTET_STRING,self.secret,&mut body);
+        if let Some(curve) = self.curve {
+            write(context(0),&encode(OBJECT_IDENTIFIER,curve),&mut body);
+        }
+        if let Some(public) = self.public {
+            let mut bits = vec![0x00];
+            bits.extend_from_slice(public);
+            write(context(1),&encode(BIT_STRING,&bits),&mut body);
+        }
+        encode(SEQUENCE,&body)
+    }
+
+    /// parse from der.
+    pub fn from_der(der: &'a [u8]) -> Result<Self,Error> {
+        let mut outer = Reader::new(der);
+        let mut body = Reader::new(outer.read(SEQUENCE)?);
+        outer.finish()?;
+        if body.read(INTEGER)? != [0x01] {
+            return Err(Error::MalformedEncoding);
+        }
+        let secret = body.read(OCTET_STRING)?;
+        let curve = match body.read_optional(context(0))? {
+            Some(params) => {
+                let mut params = Reader::new(params);
+                let oid = params.read(OBJECT_IDENTIFIER)?;
+                params.finish()?;
+                Some(oid)
+            },
+            None => None,
+        };
+        let public = match body.read_optional(context(1))? {
+            Some(bits) => {
+                let mut bits = Reader::new(bits);
+                let public = read_bit_string(&mut bits)?;
+                bits.finish()?;
+                Some(public)
+            },
+            None => None,
+        };
+        body.finish()?;
+        Ok(EcPrivateKey { secret, curve, public })
+    }
+}
+
+
+/// encode an `AlgorithmIdentifier` for an ec key on the named curve `curve`.
+pub fn ec_algorithm(curve: &[u8]) -> Vec<u8> {
+    let mut body = encode(OBJECT_IDENTIFIER,EC_PUBLIC_KEY);
+    write(OBJECT_IDENTIFIER,curve,&mut body);
+    encode(SEQUENCE,&body)
+}
+
+
+/// read an ec `AlgorithmIdentifier`, returning the named curve.  any other
+/// algorithm yields `Error::InvalidKey`.
+pub fn read_ec_algorithm<'a>(reader: &mut Reader<'a>) -> Result<&'a [u8],Error> {
+    let mut algorithm = Reader::new(reader.read(SEQUENCE)?);
+    if algorithm.read(OBJECT_IDENTIFIER)? != EC_PUBLIC_KEY {
+        return Err(Error::InvalidKey);
+    }
+    let curve = algorithm.read(OBJECT_IDENTIFIER)?;
+    algorithm.finish()?;
+    Ok(curve)
+}
+
+
+/// the parts of a pkcs8 `PrivateKeyInfo` (rfc5208) holding an ec key.
+pub struct PrivateKeyInfo<'a> {
+    /// the der-encoded curve identifier.
+    pub curve: &'a [u8],
+    /// the der-encoded `ECPrivateKey`.
+    pub key: &'a [u8],
+}
+
+
+impl<'a> PrivateKeyInfo<'a> {
+
+    /// encode as der.
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut body = encode(INTEGER,&[0x00]);
+        body.extend_from_slice(&ec_algorithm(self.curve));
+        write(OCTET_STRING,self.key,&mut body);
+        encode(SEQUENCE,&body)
+    }
+
+    /// parse from der.  the `OneAsymmetricKey` extensions of rfc5958
+    /// (attributes & an embedded public key) are accepted but ignored.
+    pub fn from_der(der: &'a [u8]) -> Result<Self,Error> {
+        let mut outer = Reader::new(der);
+        let mut body = Reader::new(outer.read(SEQUENCE)?);
+        outer.finish()?;
+        match body.read(INTEGER)? {
+            [0x00] | [0x01] => { },
+            _ => return Err(Error::MalformedEncoding),
+        }
+        let curve = read_ec_algorithm(&mut body)?;
+        let key = body.read(OCTET_STRING)?;
+        body.read_optional(context(0))?;
+        body.read_optional(0x81)?;
+        body.finish()?;
+        Ok(PrivateKeyInfo { curve, key })
+    }
+}
+
+
+/// the parts of a `SubjectPublicKeyInfo` (rfc5480) holding an ec key.
+pub struct SubjectPublicKeyInfo<'a> {
+    /// the der-encoded curve identifier.
+    pub curve: &'a [u8],
+    /// the sec1-encoded public point.
+    pub public: &'a [u8],
+}
+
+
+impl<'a> SubjectPublicKeyInfo<'a> {
+
+    /// encode as der.
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut body = ec_algorithm(self.curve);
+        let mut bits = vec![0x00];
+        bits.extend_from_slice(self.public);
+        write(BIT_STRING,&bits,&mut body);
+        encode(SEQUENCE,&body)
+    }
+
+    /// parse from der.
+    pub fn from_der(der: &'a [u8]) -> Result<Self,Error> {
+        let mut outer = Reader::new(der);
+        let mut body = Reader::new(outer.read(SEQUENCE)?);
+        outer.finish()?;
+        let curve = read_ec_algorithm(&mut body)?;
+        let public = read_bit_string(&mut body)?;
+        body.finish()?;
+        Ok(SubjectPublicKeyInfo { curve, public })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use der::{self,Reader,OCTET_STRING};
+    use error::Error;
+
+    #[test]
+    fn length_ok() {
+        for &len in &[0usize,1,0x7f,0x80,0xff,0x100,0x1234] {
+            let content = vec![0xab;len];
+            let encoded = der::encode(OCTET_STRING,&content);
+            let mut reader = Reader::new(&encoded);
+            assert_eq!(reader.read(OCTET_STRING).unwrap(),&content[..]);
+            reader.finish().unwrap();
+        }
+    }
+
+    #[test]
+    fn length_err() {
+        // non-minimal long form, truncated content & trailing bytes.
+        for bad in &[&[0x04,0x81,0x01,0x00][..], &[0x04,0x02,0x00][..], &[0x04,0x00,0x00][..]] {
+            let mut reader = Reader::new(bad);
+            let rslt = reader.read(OCTET_STRING).and_then(|_| reader.finish());
+            assert_eq!(rslt,Err(Error::MalformedEncoding));
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! hmac-drbg with sha-256 (nist sp 800-90a §10.1.2), without prediction
+//! resistance or additional input.  `random` keeps one per thread, seeded
+//! from the os, so that drawing randomness doesn't cost a syscall per use.
+use hmac::{Hmac,Mac};
+use sha2::Sha256;
+use arith;
+
+
+type HmacSha256 = Hmac<Sha256>;
+
+
+/// size of an hmac-sha256 output.
+const HLEN: usize = 32;
+
+
+/// most bytes returned by a single `generate` call (sp 800-90a allows up to
+/// `2^19` bits).
+pub const MAX_REQUEST: usize = 1 << 16;
+
+
+/// the working state of an hmac-drbg instance.
+pub struct Drbg {
+    k: [u8;HLEN],
+    v: [u8;HLEN],
+    requests: u64,
+}
+
+
+impl Drbg {
+
+    /// instantiate from `seed`, the entropy input followed by the nonce.
+    pub fn new(seed: &[u8]) -> Self {
+        let mut drbg = Drbg { k: [0x00;HLEN], v: [0x01;HLEN], requests: 0 };
+        drbg.update(seed);
+        drbg
+    }
+
+    /// number of `generate` calls made since instantiation.
+    pub fn requests(&self) -> u64 { self.requests }
+
+    /// fill `out` (at most `MAX_REQUEST` bytes) with pseudorandom bytes.
+    pub fn generate(&mut self, out: &mut [u8]) {
+        assert!(out.len() <= MAX_REQUEST,"drbg request too large");
+        for chunk in out.chunks_mut(HLEN) {
+            self.v = self.hmac(&[&self.v]);
+            chunk.copy_from_slice(&self.v[..chunk.len()]);
+        }
+        self.update(&[]);
+        self.requests += 1;
+    }
+
+    /// the `HMAC_DRBG_Update` function.
+    fn update(&mut self, data: &[u8]) {
+        self.k = self.hmac(&[&self.v,&[0x00],data]);
+        self.v = self.hmac(&[&self.v]);
+        if !data.is_empty() {
+            self.k = self.hmac(&[&self.v,&[0x01],data]);
+            self.v = self.hmac(&[&self.v]);
+        }
+    }
+
+    /// compute `HMAC_K(parts[0] || parts[1] || ...)`.
+    fn hmac(&self, parts: &[&[u8]]) -> [u8;HLEN] {
+        let mut mac = HmacSha256::new_from_slice(&self.k).expect("hmac accepts keys of any length");
+        for part in parts {
+            mac.update(part);
+        }
+        let mut out = [0u8;HLEN];
+        out.copy_from_slice(&mac.finalize().into_bytes());
+        out
+    }
+}
+
+
+impl Drop for Drbg {
+    fn drop(&mut self) {
+        arith::wipe_bytes(&mut self.k);
+        arith::wipe_bytes(&mut self.v);
+    }
+}
+
+
+/// run the known-answer test from nist cavp `HMAC_DRBG.rsp` ([SHA-256] with
+/// no prediction resistance, reseeding, personalization or additional
+/// input, count 0), returning whether the output matched.
+pub fn self_test() -> bool {
+    const SEED: [u8;48] = [
+        0xca, 0x85, 0x19, 0x11, 0x34, 0x93, 0x84, 0xbf, 0xfe, 0x89, 0xde, 0x1c, 0xbd, 0xc4, 0x6e, 0x68,
+        0x31, 0xe4, 0x4d, 0x34, 0xa4, 0xfb, 0x93, 0x5e, 0xe2, 0x85, 0xdd, 0x14, 0xb7, 0x1a, 0x74, 0x88,
+        0x65, 0x9b, 0xa9, 0x6c, 0x60, 0x1d, 0xc6, 0x9f, 0xc9, 0x02, 0x94, 0x08, 0x05, 0xec, 0x0c, 0xa8,
+    ];
+    const EXPECT: [u8;128] = [
+        0xe5, 0x28, 0xe9, 0xab, 0xf2, 0xde, 0xce, 0x54, 0xd4, 0x7c, 0x7e, 0x75, 0xe5, 0xfe, 0x30, 0x21,
+        0x49, 0xf8, 0x17, 0xea, 0x9f, 0xb4, 0xbe, 0xe6, 0xf4, 0x19, 0x96, 0x97, 0xd0, 0x4d, 0x5b, 0x89,
+        0xd5, 0x4f, 0xbb, 0x97, 0x8a, 0x15, 0xb5, 0xc4, 0x43, 0xc9, 0xec, 0x21, 0x03, 0x6d, 0x24, 0x60,
+        0xb6, 0xf7, 0x3e, 0xba, 0xd0, 0xdc, 0x2a, 0xba, 0x6e, 0x62, 0x4a, 0xbf, 0x07, 0x74, 0x5b, 0xc1,
+        0x07, 0x69, 0x4b, 0xb7, 0x54, 0x7b, 0xb0, 0x99, 0x5f, 0x70, 0xde, 0x25, 0xd6, 0xb2, 0x9e, 0x2d,
+        0x30, 0x11, 0xbb, 0x19, 0xd2, 0x76, 0x76, 0xc0, 0x71, 0x62, 0xc8, 0xb5, 0xcc, 0xde, 0x06, 0x68,
+        0x96, 0x1d, 0xf8, 0x68, 0x03, 0x48, 0x2c, 0xb3, 0x7e, 0xd6, 0xd5, 0xc0, 0xbb, 0x8d, 0x50, 0xcf,
+        0x1f, 0x50, 0xd4, 0x76, 0xaa, 0x04, 0x58, 0xbd, 0xab, 0xa8, 0x06, 0xf4, 0x8b, 0xe9, 0xdc, 0xb8,
+    ];
+    let mut drbg = Drbg::new(&SEED);
+    let mut out = [0u8;128];
+    drbg.generate(&mut out);
+    drbg.generate(&mut out);
+    out[..] == EXPECT[..]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use drbg::{Drbg,self_test};
+
+    #[test]
+    fn drbg_ok() {
+        assert!(self_test());
+        let mut drbg = Drbg::new(&[0u8;48]);
+        let (mut a, mut b) = ([0u8;40], [0u8;40]);
+        drbg.generate(&mut a);
+        drbg.generate(&mut b);
+        assert!(a != b);
+        assert_eq!(drbg.requests(),2);
+    }
+}
@@ -0,0 +1,73 @@
+//! ecdsa over the portable arithmetic in `arith`, for operations where the
+//! nonce must be chosen by the wrapper rather than by `easy-ecc`, & for
+//! curves which `easy-ecc` doesn't support.
+use arith::{self,Curve,Limbs,Point,MAX_LIMBS};
+
+
+/// sign `hash` with secret scalar `d` & nonce `k`, writing `r || s` into
+/// `sig` (two big-endian scalars of `sig.len() / 2` bytes each).  returns
+/// `false` if `k` yields a degenerate signature & another must be tried.
+#[cfg(feature = "secp256r1")]
+pub fn sign_with_nonce(curve: &Curve, d: &Limbs, hash: &[u8], k: &Limbs, sig: &mut [u8]) -> bool {
+    sign_with_nonce_recoverable(curve,d,hash,k,sig).is_some()
+}
+
+
+/// sign as `sign_with_nonce` does, returning the recovery id of the
+/// signature (bit 0 is the parity of `R.y`, & bit 1 is set if `R.x >= n`),
+/// or `None` if another nonce must be tried.
+pub fn sign_with_nonce_recoverable(curve: &Curve, d: &Limbs, hash: &[u8], k: &Limbs, sig: &mut [u8]) -> Option<u8> {
+    let (fq, len) = (&curve.fq, sig.len() / 2);
+    let (x, y) = curve.to_affine(&curve.mul(&curve.generator(),k))?;
+    let id = (y[0] & 1) as u8 | if fq.contains(&x) { 0 } else { 2 };
+    let r = fq.reduce(&x);
+    let z = fq.reduce(&arith::bits2int(hash,fq.bits()));
+    let mut kinv = fq.inv(&fq.mont_form(k));
+    let mut rd = fq.mul(&fq.mont_form(&r),&fq.mont_form(d));
+    let mut s = fq.canonical(&fq.mul(&kinv,&fq.add(&fq.mont_form(&z),&rd)));
+    let valid = !arith::is_zero(&r) && !arith::is_zero(&s);
+    if valid {
+        arith::to_be(&r,&mut sig[..len]);
+        arith::to_be(&s,&mut sig[len..]);
+    }
+    arith::wipe(&mut kinv);
+    arith::wipe(&mut rd);
+    arith::wipe(&mut s);
+    if valid { Some(id) } else { None }
+}
+
+
+/// verify signature `(r,s)` over `hash` against public point `q`.  `r` & `s`
+/// must already be known to lie in the range `[1,n)`.
+#[cfg_attr(not(any(feature = "brainpoolp256r1", feature = "secp256k1", feature = "secp521r1")), allow(dead_code))]
+pub fn verify(curve: &Curve, q: &Point, hash: &[u8], r: &Limbs, s: &Limbs) -> bool {
+    let fq = &curve.fq;
+    let z = fq.reduce(&arith::bits2int(hash,fq.bits()));
+    let w = fq.inv(&fq.mont_form(s));
+    let u1 = fq.canonical(&fq.mul(&fq.mont_form(&z),&w));
+    let u2 = fq.canonical(&fq.mul(&fq.mont_form(r),&w));
+    let point = curve.add(&curve.mul(&curve.generator(),&u1),&curve.mul(q,&u2));
+    match curve.to_affine(&point) {
+        Some((x,_)) => fq.reduce(&x) == *r,
+        None => false,
+    }
+}
+
+
+/// recover the public point from signature `(r,s)` over `hash` & its
+/// recovery id (see `sign_with_nonce_recoverable`), as in sec1 §4.1.6.
+/// `r` & `s` must already be known to lie in the range `[1,n)`.  returns
+/// `None` if no point corresponds to the signature.
+pub fn recover(curve: &Curve, hash: &[u8], r: &Limbs, s: &Limbs, id: u8) -> Option<Point> {
+    let fq = &curve.fq;
+    // `r + n` may not fit the limbs (as on secp384r1), in which case it's
+    // certainly not a field element.
+    let x = if id & 2 == 0 { *r } else { arith::checked_add(r,&curve.order())? };
+    let big_r = curve.lift_x(&x,id & 1 == 1)?;
+    let z = fq.reduce(&arith::bits2int(hash,fq.bits()));
+    let w = fq.inv(&fq.mont_form(r));
+    let u1 = fq.canonical(&fq.sub(&[0;MAX_LIMBS],&fq.mul(&fq.mont_form(&z),&w)));
+    let u2 = fq.canonical(&fq.mul(&fq.mont_form(s),&w));
+    let q = curve.add(&curve.mul(&curve.generator(),&u1),&curve.mul(&big_r,&u2));
+    curve.to_affine(&q).map(|_| q)
+}
@@ -0,0 +1,232 @@
+//! the curve-independent half of ecies (the `ecies` module of each curve,
+//! & x25519's sealed boxes): key derivation, the aes-256-gcm seal, & the
+//! wire format.
+//!
+//! a version `0x01` ciphertext is laid out as
+//!
+//! ```text
+//! version (0x01) || ephemeral public key (compressed sec1, or x25519 u) || aes-256-gcm ciphertext || tag (16 bytes)
+//! ```
+//!
+//! the aes key is hkdf-sha256 over the ecdh shared secret (with an empty
+//! salt), whose `info` is `INFO || ephemeral key || recipient key`, so that
+//! a ciphertext is bound to both keys.  the header (version & ephemeral key)
+//! is authenticated as associated data.  each key encrypts exactly one
+//! message, so the gcm nonce is fixed at zero.  wrapped data keys
+//! (`Public::wrap_key`) use the same format under `KEY_INFO`, so that they
+//! can't be passed off as messages or vice versa.
+//!
+//! a multi-recipient envelope instead seals the plaintext under a random
+//! content key, & wraps that key to each recipient under one shared
+//! ephemeral key (with `WRAP_INFO` in place of `INFO`).  its compact
+//! encoding is
+//!
+//! ```text
+//! version (0x01) || ephemeral public key || recipient count (u16, big-endian) || wrapped keys (48 bytes each) || body || tag (16 bytes)
+//! ```
+//!
+//! where the body is authenticated with the version & ephemeral key, as for
+//! a single ciphertext.
+use aes_gcm::{Aes256Gcm,KeyInit,Nonce};
+use aes_gcm::aead::{Aead,Payload};
+use arith;
+use hkdf;
+use error::Error;
+
+
+/// the current (& only) wire format version.
+pub const VERSION: u8 = 0x01;
+
+/// size of an aes-gcm authentication tag.
+pub const TAG_LEN: usize = 16;
+
+/// size of an envelope's content key.
+pub const CONTENT_KEY_LEN: usize = hkdf::KEY_LEN;
+
+/// size of a content key wrapped to one recipient.
+pub const WRAPPED_KEY_LEN: usize = CONTENT_KEY_LEN + TAG_LEN;
+
+/// most recipients an envelope can have.
+pub const MAX_RECIPIENTS: usize = 0xffff;
+
+/// hkdf `info` prefix of version `0x01` messages.
+pub const INFO: &[u8] = b"easy-ecc/ecies/v1";
+
+/// hkdf `info` prefix of version `0x01` wrapped data keys.
+pub const KEY_INFO: &[u8] = b"easy-ecc/ecies/v1/key";
+
+/// hkdf `info` prefix for wrapping an envelope's content key.
+const WRAP_INFO: &[u8] = b"easy-ecc/ecies/v1/wrap";
+
+/// the gcm nonce (see the module docs).
+const NONCE: [u8;12] = [0u8;12];
+
+
+/// bytes added to a plaintext for a curve whose compressed points are
+/// `point_len` bytes long.
+pub fn overhead(point_len: usize) -> usize { 1 + point_len + TAG_LEN }
+
+
+/// encrypt `plaintext` under the `shared` secret between the `ephemeral` &
+/// `recipient` keys (both compressed), returning the full ciphertext.
+/// `info` is `INFO` for messages or `KEY_INFO` for data keys.
+pub fn seal(info: &[u8], ephemeral: &[u8], recipient: &[u8], shared: &[u8], plaintext: &[u8]) -> Result<Vec<u8>,Error> {
+    let cipher = cipher(info,ephemeral,recipient,shared)?;
+    let mut out = header(ephemeral);
+    let body = seal_with(&cipher,&out,plaintext);
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+
+/// get the ephemeral key from the header of a ciphertext for a curve whose
+/// compressed points are `point_len` bytes long.  a ciphertext which is too
+/// short, or of an unknown version, yields `Error::MalformedEncoding`.
+pub fn ephemeral(ciphertext: &[u8], point_len: usize) -> Result<&[u8],Error> {
+    if ciphertext.len() < overhead(point_len) || ciphertext[0] != VERSION {
+        return Err(Error::MalformedEncoding);
+    }
+    Ok(&ciphertext[1..1+point_len])
+}
+
+
+/// decrypt a ciphertext (whose header has been checked by `ephemeral`) under
+/// the `shared` secret, where `recipient` is the compressed public key it
+/// was sent to & `info` is as for `seal`.  a ciphertext which fails
+/// authentication yields `Error::DecryptionFailed`.
+pub fn open(info: &[u8], recipient: &[u8], shared: &[u8], ciphertext: &[u8], point_len: usize) -> Result<Vec<u8>,Error> {
+    let (header, body) = ciphertext.split_at(1 + point_len);
+    let cipher = cipher(info,&header[1..],recipient,shared)?;
+    open_with(&cipher,header,body)
+}
+
+
+/// wrap an envelope's `content_key` to `recipient` under the `shared`
+/// secret between it & the envelope's `ephemeral` key.
+pub fn wrap(ephemeral: &[u8], recipient: &[u8], shared: &[u8], content_key: &[u8;CONTENT_KEY_LEN]) -> Result<[u8;WRAPPED_KEY_LEN],Error> {
+    let cipher = cipher(WRAP_INFO,ephemeral,recipient,shared)?;
+    let mut out = [0u8;WRAPPED_KEY_LEN];
+    out.copy_from_slice(&seal_with(&cipher,&[],content_key));
+    Ok(out)
+}
+
+
+/// unwrap a content key wrapped by `wrap`, giving `None` if it wasn't
+/// wrapped to `recipient` (or was tampered with).
+pub fn unwrap(ephemeral: &[u8], recipient: &[u8], shared: &[u8], wrapped: &[u8;WRAPPED_KEY_LEN]) -> Result<Option<[u8;CONTENT_KEY_LEN]>,Error> {
+    let cipher = cipher(WRAP_INFO,ephemeral,recipient,shared)?;
+    Ok(open_with(&cipher,&[],wrapped).ok().map(|mut key| {
+        let mut out = [0u8;CONTENT_KEY_LEN];
+        out.copy_from_slice(&key);
+        arith::wipe_bytes(&mut key);
+        out
+    }))
+}
+
+
+/// seal an envelope's body under its content key.
+pub fn seal_body(content_key: &[u8;CONTENT_KEY_LEN], ephemeral: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    let cipher = Aes256Gcm::new_from_slice(content_key).expect("aes-256 keys are 32 bytes");
+    seal_with(&cipher,&header(ephemeral),plaintext)
+}
+
+
+/// open an envelope's body under its content key, failing with
+/// `Error::DecryptionFailed` if it was tampered with.
+pub fn open_body(content_key: &[u8;CONTENT_KEY_LEN], ephemeral: &[u8], body: &[u8]) -> Result<Vec<u8>,Error> {
+    let cipher = Aes256Gcm::new_from_slice(content_key).expect("aes-256 keys are 32 bytes");
+    open_with(&cipher,&header(ephemeral),body)
+}
+
+
+/// encode an envelope of the given wire format `version` compactly (see the
+/// module docs).  a version other than `VERSION` yields
+/// `Error::MalformedEncoding`; no recipients, or more than `MAX_RECIPIENTS`
+/// (whose count wouldn't fit its `u16`), yields `Error::InvalidParticipants`.
+pub fn encode_envelope(version: u8, ephemeral: &[u8], keys: &[&[u8]], body: &[u8]) -> Result<Vec<u8>,Error> {
+    if version != VERSION {
+        return Err(Error::MalformedEncoding);
+    }
+    if keys.is_empty() || keys.len() > MAX_RECIPIENTS {
+        return Err(Error::InvalidParticipants);
+    }
+    let mut out = Vec::with_capacity(1 + ephemeral.len() + 2 + keys.len() * WRAPPED_KEY_LEN + body.len());
+    out.push(version);
+    out.extend_from_slice(ephemeral);
+    out.extend_from_slice(&[(keys.len() >> 8) as u8,keys.len() as u8]);
+    for key in keys {
+        out.extend_from_slice(key);
+    }
+    out.extend_from_slice(body);
+    Ok(out)
+}
+
+
+/// the parts of a compactly encoded envelope.
+pub struct EnvelopeParts<'a> {
+    /// the wire format version.
+    pub version: u8,
+    /// the ephemeral public key.
+    pub ephemeral: &'a [u8],
+    /// the wrapped content keys.
+    pub keys: Vec<[u8;WRAPPED_KEY_LEN]>,
+    /// the sealed body & its tag.
+    pub body: &'a [u8],
+}
+
+
+/// split a compact envelope for a curve whose points are `point_len` bytes
+/// long into its parts.  an envelope which is truncated, of an unknown
+/// version, or has no recipients yields `Error::MalformedEncoding`.
+pub fn decode_envelope<'a>(bytes: &'a [u8], point_len: usize) -> Result<EnvelopeParts<'a>,Error> {
+    let start = 1 + point_len + 2;
+    if bytes.len() < start || bytes[0] != VERSION {
+        return Err(Error::MalformedEncoding);
+    }
+    let count = (bytes[start-2] as usize) << 8 | bytes[start-1] as usize;
+    let end = start + count * WRAPPED_KEY_LEN;
+    if count == 0 || bytes.len() < end + TAG_LEN {
+        return Err(Error::MalformedEncoding);
+    }
+    let keys = bytes[start..end].chunks(WRAPPED_KEY_LEN).map(|chunk| {
+        let mut key = [0u8;WRAPPED_KEY_LEN];
+        key.copy_from_slice(chunk);
+        key
+    }).collect();
+    Ok(EnvelopeParts { version: bytes[0], ephemeral: &bytes[1..1+point_len], keys, body: &bytes[end..] })
+}
+
+
+/// the header of a ciphertext or envelope: the version & ephemeral key.
+fn header(ephemeral: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(1 + ephemeral.len());
+    out.push(VERSION);
+    out.extend_from_slice(ephemeral);
+    out
+}
+
+
+/// seal `plaintext` with `aad` under the fixed nonce.
+fn seal_with(cipher: &Aes256Gcm, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    cipher.encrypt(Nonce::from_slice(&NONCE),Payload { msg: plaintext, aad })
+        .expect("aes-gcm accepts plaintexts up to 64 gib")
+}
+
+
+/// open `ciphertext` with `aad` under the fixed nonce.
+fn open_with(cipher: &Aes256Gcm, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>,Error> {
+    cipher.decrypt(Nonce::from_slice(&NONCE),Payload { msg: ciphertext, aad })
+        .map_err(|_| Error::DecryptionFailed)
+}
+
+
+/// derive the aes-256-gcm cipher for a pair of keys, under the given
+/// `info` prefix.
+fn cipher(prefix: &[u8], ephemeral: &[u8], recipient: &[u8], shared: &[u8]) -> Result<Aes256Gcm,Error> {
+    let mut key = [0u8;hkdf::KEY_LEN];
+    let info = [prefix,ephemeral,recipient].concat();
+    hkdf::derive(&[],shared,&info,&mut key)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).expect("aes-256 keys are 32 bytes");
+    arith::wipe_bytes(&mut key);
+    Ok(cipher)
+}
@@ -0,0 +1,445 @@
+//! types and functions for ed25519 signatures, as described in rfc8032.
+//!
+//! only built with the `ed25519` feature.  `easy-ecc` only implements short
+//! weierstrass curves, so this module is backed by the crate's portable
+//! field arithmetic.  unlike the ecdsa curve modules, messages are signed
+//! whole (ed25519 hashes them internally with sha-512), & keys & signatures
+//! use the little-endian encodings of rfc8032.
+use arith::{self,Limbs,Modulus,MAX_LIMBS};
+use sha2::{Sha512,Digest};
+use error::Error;
+use random;
+
+
+/// size of curve.
+const BYTES: usize = 32;
+
+
+/// size of a serialized public key.
+pub const PUBLIC_KEY_SIZE: usize = BYTES;
+
+/// size of a serialized secret key (the 32-byte seed of rfc8032).
+pub const SECRET_KEY_SIZE: usize = BYTES;
+
+/// size of a serialized signature.
+pub const SIGNATURE_SIZE: usize = BYTES * 2;
+
+
+/// the field prime `2^255 - 19`.
+const P: &[u64] = &[0xFFFFFFFFFFFFFFED, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0x7FFFFFFFFFFFFFFF];
+
+
+/// the group order `2^252 + 27742317777372353535851937790883648493`.
+const L: &[u64] = &[0x5812631A5CF5D3ED, 0x14DEF9DEA2F79CD6, 0x0000000000000000, 0x1000000000000000];
+
+
+/// the curve constant `d = -121665/121666`.
+const D: &[u64] = &[0x75EB4DCA135978A3, 0x00700A4D4141D8AB, 0x8CC740797779E898, 0x52036CEE2B6FFE73];
+
+
+/// encoding of the base point (`y = 4/5` with even `x`).
+const BASE: [u8;BYTES] = [
+    0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66
+];
+
+
+/// a public ed25519 key (an encoded curve point).
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,PartialOrd,Ord)]
+pub struct Public([u8;BYTES]);
+impl_newtype_bytearray!(Public,BYTES);
+impl_serde_bytearray!(Public,BYTES);
+impl_hex_fmt!(Public);
+impl_from_slice!(Public,PUBLIC_KEY_SIZE);
+impl_bytes!(Public,PUBLIC_KEY_SIZE);
+
+impl Default for Public {
+    fn default() -> Self { Public([0u8;BYTES]) }
+}
+
+impl Public {
+    /// verify a signature over `msg` against this public key.
+    ///
+    /// a signature which simply doesn't match yields
+    /// `Error::InvalidSignature`, while a key which isn't a valid curve
+    /// point yields `Error::InvalidKey` & a signature which can't be decoded
+    /// yields `Error::MalformedSignature`.
+    pub fn verify(&self, msg: &[u8], sig: &Signature) -> Result<(),Error> {
+        let ed = Edwards::new();
+        let a = ed.decode(&self.0).ok_or(Error::InvalidKey)?;
+        let mut rbytes = [0u8;BYTES];
+        rbytes.copy_from_slice(&sig.0[..BYTES]);
+        let r = ed.decode(&rbytes).ok_or(Error::MalformedSignature)?;
+        let s = from_le(&sig.0[BYTES..]);
+        if !ed.q.contains(&s) {
+            return Err(Error::MalformedSignature);
+        }
+        let k = ed.challenge(&rbytes,&self.0,msg);
+        let lhs = ed.mul(&ed.base,&s);
+        let rhs = ed.add(&r,&ed.mul(&a,&k));
+        if ed.encode(&lhs) == ed.encode(&rhs) { Ok(()) } else { Err(Error::InvalidSignature) }
+    }
+}
+
+
+/// a secret ed25519 key (the 32-byte seed of rfc8032).
+pub struct Secret([u8;BYTES]);
+impl_newtype_bytearray!(Secret,BYTES);
+impl_redacted_fmt!(Secret);
+impl_ct_eq!(Secret);
+impl_serde_bytearray!(Secret,BYTES);
+impl_from_slice!(Secret,SECRET_KEY_SIZE);
+
+impl Default for Secret {
+    fn default() -> Self { Secret([0u8;BYTES]) }
+}
+
+impl Secret {
+    /// generate a new random secret key.
+    pub fn generate() -> Result<Self,Error> {
+        let mut secret = Secret::default();
+        random::fill(&mut secret.0)?;
+        Ok(secret)
+    }
+
+    /// the raw secret key bytes, for the rare cases (such as export) where
+    /// they must be shown.  never log them.
+    pub fn expose_secret(&self) -> &[u8;BYTES] { &self.0 }
+
+    /// derive the public key corresponding to this secret key.
+    pub fn public(&self) -> Public {
+        let ed = Edwards::new();
+        let (mut a, mut prefix) = expand(&self.0);
+        let public = Public(ed.encode(&ed.mul(&ed.base,&a)));
+        arith::wipe(&mut a);
+        arith::wipe_bytes(&mut prefix);
+        public
+    }
+
+    /// generate a signature over `msg` with this secret key.
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        let ed = Edwards::new();
+        let q = &ed.q;
+        let (mut a, mut prefix) = expand(&self.0);
+        let public = ed.encode(&ed.mul(&ed.base,&a));
+        let mut hasher = Sha512::new();
+        hasher.update(&prefix[..]);
+        hasher.update(msg);
+        let mut r = reduce_wide(q,&hasher.finalize());
+        let rbytes = ed.encode(&ed.mul(&ed.base,&r));
+        let k = ed.challenge(&rbytes,&public,msg);
+        let mut ka = q.canonical(&q.mul(&q.mont_form(&k),&q.mont_form(&q.reduce(&a))));
+        let s = q.add(&r,&ka);
+        let mut sig = Signature::default();
+        sig.0[..BYTES].copy_from_slice(&rbytes);
+        to_le(&s,&mut sig.0[BYTES..]);
+        arith::wipe(&mut a);
+        arith::wipe(&mut r);
+        arith::wipe(&mut ka);
+        arith::wipe_bytes(&mut prefix);
+        sig
+    }
+}
+
+impl_zeroize!(Secret);
+
+
+/// an ed25519 signature (`R || S`).
+#[derive(Clone,Copy)]
+pub struct Signature([u8;BYTES*2]);
+impl_newtype_bytearray_ext!(Signature,BYTES*2);
+impl_serde_bytearray!(Signature,BYTES*2);
+impl_hex_fmt!(Signature);
+impl_from_slice!(Signature,SIGNATURE_SIZE);
+impl_bytes!(Signature,SIGNATURE_SIZE);
+
+
+/// a public/secret ed25519 keypair.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct KeyPair {
+    public: Public,
+    secret: Secret,
+}
+impl_envelope_key!("ed25519",Public,Secret,KeyPair);
+
+impl KeyPair {
+    /// generate a new random keypair.
+    pub fn generate() -> Result<Self,Error> {
+        let secret = Secret::generate()?;
+        Ok(KeyPair { public: secret.public(), secret })
+    }
+
+    /// get the public half of this keypair.
+    pub fn public(&self) -> &Public { &self.public }
+
+    /// get the secret half of this keypair.
+    pub fn secret(&self) -> &Secret { &self.secret }
+
+    /// generate a signature over `msg` with the secret key.
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        self.secret.sign(msg)
+    }
+}
+
+
+/// generate a new ed25519 keypair into `public` & `secret`, which are left
+/// untouched on failure.
+#[deprecated(note = "use `KeyPair::generate` or `Secret::generate`, which return the new keys")]
+pub fn keygen(public: &mut Public, secret: &mut Secret) -> Result<(),Error> {
+    let pair = KeyPair::generate()?;
+    *public = pair.public;
+    *secret = pair.secret;
+    Ok(())
+}
+
+
+/// generate a new ed25519 signature.
+pub fn sign(key: &Secret, msg: &[u8], sig: &mut Signature) -> Result<(),Error> {
+    *sig = key.sign(msg);
+    Ok(())
+}
+
+
+/// verify an ed25519 signature.
+pub fn verify(key: &Public, msg: &[u8], sig: &Signature) -> Result<(),Error> {
+    key.verify(msg,sig)
+}
+
+
+/// hash a seed into the clamped secret scalar & the nonce prefix.
+fn expand(seed: &[u8;BYTES]) -> (Limbs,[u8;BYTES]) {
+    let mut h = [0u8;BYTES*2];
+    h.copy_from_slice(&Sha512::digest(seed));
+    h[0] &= 248;
+    h[31] &= 127;
+    h[31] |= 64;
+    let a = from_le(&h[..BYTES]);
+    let mut prefix = [0u8;BYTES];
+    prefix.copy_from_slice(&h[BYTES..]);
+    arith::wipe_bytes(&mut h);
+    (a,prefix)
+}
+
+
+/// reduce a 64-byte little-endian hash modulo the group order.
+fn reduce_wide(q: &Modulus, h: &[u8]) -> Limbs {
+    // `hi * 2^256 + lo`, where `2^256` is the montgomery radix, so that
+    // `mont_form(hi)` is exactly `hi * 2^256 mod q`.
+    let lo = q.reduce(&from_le(&h[..BYTES]));
+    let hi = q.mont_form(&q.reduce(&from_le(&h[BYTES..])));
+    q.add(&lo,&hi)
+}
+
+
+/// load a little-endian byte string into limbs.
+fn from_le(bytes: &[u8]) -> Limbs {
+    let mut be = [0u8;BYTES];
+    be.copy_from_slice(bytes);
+    be.reverse();
+    arith::from_be(&be)
+}
+
+
+/// write limbs out as a little-endian byte string.
+fn to_le(limbs: &Limbs, bytes: &mut [u8]) {
+    arith::to_be(limbs,bytes);
+    bytes.reverse();
+}
+
+
+/// a point in extended coordinates `(X : Y : Z : T)` (montgomery form).
+#[derive(Clone,Copy)]
+struct Point {
+    x: Limbs,
+    y: Limbs,
+    z: Limbs,
+    t: Limbs,
+}
+
+
+/// the twisted edwards curve `-x^2 + y^2 = 1 + dx^2y^2`, with its field and
+/// scalar arithmetic set up.
+struct Edwards {
+    f: Modulus,
+    q: Modulus,
+    d: Limbs,
+    d2: Limbs,
+    base: Point,
+}
+
+
+impl Edwards {
+
+    fn new() -> Self {
+        let f = Modulus::new(P);
+        let mut d = [0u64;MAX_LIMBS];
+        d[..D.len()].copy_from_slice(D);
+        let d = f.mont_form(&d);
+        let identity = Point { x: [0;MAX_LIMBS], y: f.one(), z: f.one(), t: [0;MAX_LIMBS] };
+        let mut ed = Edwards { f, q: Modulus::new(L), d, d2: f.add(&d,&d), base: identity };
+        ed.base = ed.decode(&BASE).expect("base point is valid");
+        ed
+    }
+
+    /// the neutral element.
+    fn identity(&self) -> Point {
+        Point { x: [0;MAX_LIMBS], y: self.f.one(), z: self.f.one(), t: [0;MAX_LIMBS] }
+    }
+
+    /// complete point addition (hisil, wong, carter & dawson 2008, with
+    /// `a = -1`), valid for all inputs including doubling.
+    fn add(&self, p: &Point, q: &Point) -> Point {
+        let f = &self.f;
+        let a = f.mul(&f.sub(&p.y,&p.x),&f.sub(&q.y,&q.x));
+        let b = f.mul(&f.add(&p.y,&p.x),&f.add(&q.y,&q.x));
+        let c = f.mul(&f.mul(&p.t,&self.d2),&q.t);
+        let zz = f.mul(&p.z,&q.z);
+        let d = f.add(&zz,&zz);
+        let (e, ff, g, h) = (f.sub(&b,&a), f.sub(&d,&c), f.add(&d,&c), f.add(&b,&a));
+        Point { x: f.mul(&e,&ff), y: f.mul(&g,&h), z: f.mul(&ff,&g), t: f.mul(&e,&h) }
+    }
+
+    /// scalar multiplication by a montgomery ladder over the low 256 bits of
+    /// `k` (so the sequence of operations doesn't depend on its value).
+    fn mul(&self, p: &Point, k: &Limbs) -> Point {
+        let mut r0 = self.identity();
+        let mut r1 = *p;
+        for i in (0..256).rev() {
+            let mask = ((k[i / 64] >> (i % 64)) & 1).wrapping_neg();
+            cswap(&mut r0,&mut r1,mask);
+            r1 = self.add(&r0,&r1);
+            r0 = self.add(&r0,&r0);
+            cswap(&mut r0,&mut r1,mask);
+        }
+        r0
+    }
+
+    /// the challenge scalar `H(R || A || msg) mod q`.
+    fn challenge(&self, r: &[u8;BYTES], a: &[u8;BYTES], msg: &[u8]) -> Limbs {
+        let mut hasher = Sha512::new();
+        hasher.update(&r[..]);
+        hasher.update(&a[..]);
+        hasher.update(msg);
+        reduce_wide(&self.q,&hasher.finalize())
+    }
+
+    /// encode a point as its y-coordinate, with the sign of x in the top bit.
+    fn encode(&self, p: &Point) -> [u8;BYTES] {
+        let f = &self.f;
+        let zinv = f.inv(&p.z);
+        let x = f.canonical(&f.mul(&p.x,&zinv));
+        let y = f.canonical(&f.mul(&p.y,&zinv));
+        let mut out = [0u8;BYTES];
+        to_le(&y,&mut out);
+        out[31] |= ((x[0] & 1) as u8) << 7;
+        out
+    }
+
+    /// decode a point, checking that the encoding is canonical & that it
+    /// lies on the curve.
+    fn decode(&self, bytes: &[u8;BYTES]) -> Option<Point> {
+        let f = &self.f;
+        let sign = bytes[31] >> 7;
+        let mut ybytes = *bytes;
+        ybytes[31] &= 127;
+        let y = from_le(&ybytes);
+        if !f.contains(&y) { return None; }
+        let y = f.mont_form(&y);
+        // `x^2 = (y^2 - 1) / (dy^2 + 1)`.
+        let yy = f.mul(&y,&y);
+        let u = f.sub(&yy,&f.one());
+        let v = f.add(&f.mul(&self.d,&yy),&f.one());
+        let mut x = f.sqrt(&f.mul(&u,&f.inv(&v)))?;
+        let xc = f.canonical(&x);
+        if arith::is_zero(&xc) && sign == 1 { return None; }
+        if (xc[0] & 1) as u8 != sign {
+            x = f.sub(&[0;MAX_LIMBS],&x);
+        }
+        Some(Point { x, y, z: f.one(), t: f.mul(&x,&y) })
+    }
+}
+
+
+/// swap `a` & `b` if `mask` is all ones.
+fn cswap(a: &mut Point, b: &mut Point, mask: u64) {
+    let p0 = *a;
+    a.x = arith::select(&a.x,&b.x,mask);
+    a.y = arith::select(&a.y,&b.y,mask);
+    a.z = arith::select(&a.z,&b.z,mask);
+    a.t = arith::select(&a.t,&b.t,mask);
+    b.x = arith::select(&b.x,&p0.x,mask);
+    b.y = arith::select(&b.y,&p0.y,mask);
+    b.z = arith::select(&b.z,&p0.z,mask);
+    b.t = arith::select(&b.t,&p0.t,mask);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ed25519::{BYTES,Public,Secret,Signature,KeyPair,sign,verify};
+    use error::Error;
+    use hex::unhex;
+
+    #[test]
+    #[allow(deprecated)]
+    fn keygen_ok() {
+        use ed25519::keygen;
+        let mut public = Public::default();
+        let mut secret = Secret::default();
+        keygen(&mut public, &mut secret).unwrap();
+
+        assert!(secret != Secret::default());
+        assert_eq!(secret.public(),public);
+    }
+
+    #[test]
+    fn signing_ok() {
+        let pair = KeyPair::generate().unwrap();
+        let mut sig = Signature::default();
+        sign(pair.secret(),b"hello",&mut sig).unwrap();
+        verify(pair.public(),b"hello",&sig).unwrap();
+        assert_eq!(verify(pair.public(),b"hellO",&sig),Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn rfc8032_ok() {
+        // rfc8032 §7.1, tests 1-3.
+        let vectors = [
+            ("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+             "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a", "",
+             "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"),
+            ("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+             "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c", "72",
+             "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"),
+            ("c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+             "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025", "af82",
+             "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a"),
+        ];
+        for &(secret,public,msg,sig) in vectors.iter() {
+            let secret = Secret::from_slice(&unhex(secret)).unwrap();
+            let public = Public::from_slice(&unhex(public)).unwrap();
+            let (msg, sig) = (unhex(msg), Signature::from_slice(&unhex(sig)).unwrap());
+            assert_eq!(secret.public(),public);
+            assert!(secret.sign(&msg) == sig);
+            public.verify(&msg,&sig).unwrap();
+        }
+    }
+
+    #[test]
+    fn verify_err_kinds() {
+        let pair = KeyPair::generate().unwrap();
+        let sig = pair.sign(b"msg");
+        // `S` must be below the group order.
+        let mut raw = [0u8;BYTES*2];
+        raw.copy_from_slice(sig.as_ref());
+        for byte in raw[BYTES..].iter_mut() { *byte = 0xff; }
+        assert_eq!(pair.public().verify(b"msg",&Signature::from(raw)),Err(Error::MalformedSignature));
+        // `y = 2` isn't the y-coordinate of any curve point.
+        let mut bad = [0u8;BYTES];
+        bad[0] = 2;
+        assert_eq!(Public::from(bad).verify(b"msg",&sig),Err(Error::InvalidKey));
+    }
+}
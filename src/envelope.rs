@@ -0,0 +1,138 @@
+//! a versioned container for storing keys along with their metadata.
+//!
+//! a `KeyEnvelope` records the curve a key belongs to, when it was created
+//! & an optional label, so that stored keys can be told apart (& read back
+//! safely) as more curves & formats are added.  with the `serde` feature
+//! it serializes as a struct of its fields, the key taking whichever form
+//! its own serde support gives it.  unknown fields are ignored when
+//! deserializing, so later versions can add fields which older readers
+//! skip.
+use std::time::{SystemTime,UNIX_EPOCH};
+use error::Error;
+
+
+/// the envelope format version written by this crate.
+pub const ENVELOPE_VERSION: u32 = 1;
+
+
+/// a key type which can be stored in a `KeyEnvelope`.  implemented by the
+/// public keys, secret keys & keypairs of each curve module.
+pub trait EnvelopeKey {
+    /// the name of the key's curve (e.g. `"secp256r1"`).
+    const CURVE: &'static str;
+}
+
+
+/// a key together with its format version, curve, creation time & an
+/// optional label.
+#[derive(Debug,Clone,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct KeyEnvelope<K> {
+    /// the envelope format version (`ENVELOPE_VERSION` when written by
+    /// this version of the crate).
+    pub version: u32,
+    /// the name of the key's curve (e.g. `"secp256r1"`).
+    pub curve: String,
+    /// the creation time, in seconds since the unix epoch.
+    pub created: u64,
+    /// a label for the key, such as its purpose or owner.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub label: Option<String>,
+    /// the key itself.
+    pub key: K,
+}
+
+
+impl<K: EnvelopeKey> KeyEnvelope<K> {
+    /// wrap `key`, stamped with the current time & no label.
+    pub fn new(key: K) -> Self {
+        let created = SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_secs()).unwrap_or(0);
+        KeyEnvelope::with_created(key,created)
+    }
+
+    /// wrap `key`, stamped with a creation time in seconds since the unix
+    /// epoch (as when re-wrapping an existing key) & no label.
+    pub fn with_created(key: K, created: u64) -> Self {
+        KeyEnvelope {
+            version: ENVELOPE_VERSION,
+            curve: K::CURVE.to_owned(),
+            created,
+            label: None,
+            key,
+        }
+    }
+
+    /// set the label.
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_owned());
+        self
+    }
+
+    /// check that this envelope can be read by this version of the crate &
+    /// holds a key on the expected curve.  a newer (or zero) version yields
+    /// `Error::MalformedEncoding`, while another curve yields
+    /// `Error::InvalidKey`.
+    pub fn check(&self) -> Result<(),Error> {
+        if self.version == 0 || self.version > ENVELOPE_VERSION {
+            return Err(Error::MalformedEncoding);
+        }
+        if self.curve != K::CURVE { return Err(Error::InvalidKey); }
+        Ok(())
+    }
+
+    /// unwrap the key, having checked the envelope as `check` does.
+    pub fn into_key(self) -> Result<K,Error> {
+        self.check()?;
+        Ok(self.key)
+    }
+}
+
+
+#[cfg(all(test, feature = "secp224r1", feature = "secp256k1"))]
+mod tests {
+    use envelope::{KeyEnvelope,ENVELOPE_VERSION};
+    use error::Error;
+    use secp224r1;
+    use secp256k1;
+
+    #[test]
+    fn envelope_ok() {
+        let pair = secp256k1::KeyPair::generate().unwrap();
+        let public = *pair.public();
+        let envelope = KeyEnvelope::with_created(public,1700000000).label("signing");
+        assert_eq!(envelope.version,ENVELOPE_VERSION);
+        assert_eq!(envelope.curve,"secp256k1");
+        assert_eq!(envelope.created,1700000000);
+        assert_eq!(envelope.label.as_ref().map(|label| &label[..]),Some("signing"));
+        assert!(envelope.into_key().unwrap() == *pair.public());
+        let envelope = KeyEnvelope::new(pair);
+        assert!(envelope.created > 1700000000);
+        assert_eq!(envelope.label,None);
+        envelope.check().unwrap();
+    }
+
+    #[test]
+    fn envelope_err() {
+        let secret = secp224r1::Secret::generate().unwrap();
+        let mut envelope = KeyEnvelope::new(secret);
+        envelope.version = ENVELOPE_VERSION + 1;
+        assert_eq!(envelope.check(),Err(Error::MalformedEncoding));
+        envelope.version = 0;
+        assert_eq!(envelope.check(),Err(Error::MalformedEncoding));
+        envelope.version = ENVELOPE_VERSION;
+        envelope.curve = "secp256k1".to_owned();
+        assert_eq!(envelope.into_key().err(),Some(Error::InvalidKey));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn envelope_serde_ok() {
+        use serde_json;
+        let pair = secp256k1::KeyPair::generate().unwrap();
+        let envelope = KeyEnvelope::with_created(pair,1700000000).label("signing");
+        let json = serde_json::to_string(&envelope).unwrap();
+        let back: KeyEnvelope<secp256k1::KeyPair> = serde_json::from_str(&json).unwrap();
+        assert!(back == envelope);
+        back.check().unwrap();
+    }
+}
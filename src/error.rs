@@ -0,0 +1,129 @@
+//! error type shared by all curve modules.
+use std::error;
+use std::fmt;
+
+
+/// an error produced by a cryptographic operation.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Error {
+    /// the random number generator produced unusable output (such as
+    /// scalars which stayed out of range across every retry).
+    RngFailure,
+    /// no randomness could be obtained from the entropy source (the
+    /// operating system, or a caller-supplied rng), so the operation was
+    /// abandoned.  unlike other errors, this points at the environment
+    /// rather than the inputs, & retrying is unlikely to help.
+    RandomnessUnavailable,
+    /// a randomized operation kept yielding degenerate results (such as a
+    /// zero signature component) until its retry limit, although the random
+    /// number generator was working.
+    RetriesExhausted,
+    /// a key is malformed or otherwise unusable.
+    InvalidKey,
+    /// a public key has a sec1 prefix byte other than `0x02`/`0x03` (or
+    /// `0x04` where uncompressed points are accepted), or encodes the point
+    /// at infinity.
+    InvalidPointEncoding,
+    /// a secret key is zero or not less than the group order.
+    InvalidSecretKey,
+    /// a signature did not verify.
+    InvalidSignature,
+    /// a signature is not a well-formed pair of scalars.
+    MalformedSignature,
+    /// the underlying `easy-ecc` call reported failure.  no operation
+    /// produces this any longer: only verification still calls `easy-ecc`,
+    /// & it reports failure as `InvalidSignature`.
+    #[deprecated(note = "no longer produced; kept so that existing matches still compile")]
+    FfiFailure,
+    /// a freshly created signature failed to verify, suggesting a fault
+    /// (such as a glitched computation) while signing.
+    FaultDetected,
+    /// a der or other structured encoding could not be parsed.
+    MalformedEncoding,
+    /// a threshold protocol was given an unusable set of participants (such
+    /// as a missing or repeated index, or fewer signers than the threshold).
+    InvalidParticipants,
+    /// a ciphertext failed authentication: it was tampered with, or wasn't
+    /// encrypted to this key.
+    DecryptionFailed,
+    /// a hierarchical key derivation was impossible: a hardened child of a
+    /// public key, a child beyond the maximum depth, an unusable seed, or
+    /// a child which landed on an invalid key.
+    InvalidDerivation,
+    /// a byte string had the wrong length for the type being constructed.
+    WrongLength {
+        /// length required by the type.
+        expected: usize,
+        /// length actually supplied.
+        got: usize,
+    },
+}
+
+
+impl fmt::Display for Error {
+    #[allow(deprecated)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::RngFailure => f.write_str("random number generation failed"),
+            Error::RandomnessUnavailable => f.write_str("randomness unavailable"),
+            Error::RetriesExhausted => f.write_str("retry limit exhausted"),
+            Error::InvalidKey => f.write_str("invalid key"),
+            Error::InvalidPointEncoding => f.write_str("invalid point encoding"),
+            Error::InvalidSecretKey => f.write_str("invalid secret key"),
+            Error::InvalidSignature => f.write_str("invalid signature"),
+            Error::MalformedSignature => f.write_str("malformed signature"),
+            Error::FfiFailure => f.write_str("easy-ecc operation failed"),
+            Error::FaultDetected => f.write_str("signature self-check failed"),
+            Error::MalformedEncoding => f.write_str("malformed encoding"),
+            Error::InvalidParticipants => f.write_str("invalid set of participants"),
+            Error::DecryptionFailed => f.write_str("decryption failed"),
+            Error::InvalidDerivation => f.write_str("invalid key derivation"),
+            Error::WrongLength { expected, got } => {
+                write!(f,"wrong length (expected {} bytes, got {})",expected,got)
+            },
+        }
+    }
+}
+
+
+impl error::Error for Error { }
+
+
+/// the failures of a batch verification: the index of each item which
+/// didn't verify, along with the reason.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct BatchError {
+    failures: Vec<(usize,Error)>,
+}
+
+
+impl BatchError {
+
+    /// collect the failures of a batch, if there were any.
+    pub(crate) fn from_failures(failures: Vec<(usize,Error)>) -> Result<(),BatchError> {
+        if failures.is_empty() { Ok(()) } else { Err(BatchError { failures }) }
+    }
+
+    /// the failed items, as `(index, error)` pairs in ascending index order.
+    pub fn failures(&self) -> &[(usize,Error)] { &self.failures }
+
+    /// the indexes of the failed items, in ascending order.
+    pub fn indexes(&self) -> Vec<usize> {
+        self.failures.iter().map(|&(index,_)| index).collect()
+    }
+}
+
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{} item(s) failed verification",self.failures.len())?;
+        if let Some(&(index,err)) = self.failures.first() {
+            write!(f," (first at index {}: {})",index,err)?;
+        }
+        Ok(())
+    }
+}
+
+
+impl error::Error for BatchError { }
+
@@ -0,0 +1,86 @@
+//! public key fingerprints & short key ids, for logging & indexing keys.
+//!
+//! a `Fingerprint` is the sha-256 hash of a key's compressed sec1
+//! encoding, & a `KeyId` its first eight bytes.  both display as lowercase
+//! hex.  hashing the compressed form means a key gets the same fingerprint
+//! however it was parsed.
+use std::fmt;
+use sha2::{Sha256,Digest};
+use base64;
+use hex;
+
+
+/// size of a fingerprint.
+pub const FINGERPRINT_SIZE: usize = 32;
+
+/// size of a key id.
+pub const KEY_ID_SIZE: usize = 8;
+
+
+/// the sha-256 hash of a public key's compressed sec1 encoding.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct Fingerprint([u8;FINGERPRINT_SIZE]);
+
+impl Fingerprint {
+    /// fingerprint a compressed sec1 point.
+    pub(crate) fn of(point: &[u8]) -> Self {
+        let mut hash = [0u8;FINGERPRINT_SIZE];
+        hash.copy_from_slice(&Sha256::digest(point));
+        Fingerprint(hash)
+    }
+
+    /// the short key id: the first eight bytes of the fingerprint.
+    pub fn key_id(&self) -> KeyId {
+        let mut id = [0u8;KEY_ID_SIZE];
+        id.copy_from_slice(&self.0[..KEY_ID_SIZE]);
+        KeyId(id)
+    }
+
+    /// the fingerprint as unpadded base64url.
+    pub fn to_base64(&self) -> String { base64::encode(&self.0,base64::URL_SAFE,false) }
+}
+
+impl AsRef<[u8]> for Fingerprint {
+    fn as_ref(&self) -> &[u8] { &self.0 }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str(&hex::encode(&self.0)) }
+}
+
+
+/// a short key id: the first eight bytes of a public key's `Fingerprint`.
+/// ids are short enough to collide by chance among billions of keys, so
+/// they suit logs & lookups but shouldn't stand in for the full key.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct KeyId([u8;KEY_ID_SIZE]);
+
+impl KeyId {
+    /// the key id as unpadded base64url.
+    pub fn to_base64(&self) -> String { base64::encode(&self.0,base64::URL_SAFE,false) }
+}
+
+impl AsRef<[u8]> for KeyId {
+    fn as_ref(&self) -> &[u8] { &self.0 }
+}
+
+impl fmt::Display for KeyId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str(&hex::encode(&self.0)) }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use fingerprint::Fingerprint;
+
+    #[test]
+    fn fingerprint_ok() {
+        // the sha-256 hash of `"abc"`.
+        let fingerprint = Fingerprint::of(b"abc");
+        assert_eq!(fingerprint.to_string(),"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(fingerprint.key_id().to_string(),"ba7816bf8f01cfea");
+        assert_eq!(fingerprint.key_id().as_ref(),&fingerprint.as_ref()[..8]);
+        assert_eq!(fingerprint.key_id().to_base64(),"ungWv48Bz-o");
+        assert_eq!(fingerprint.to_base64(),"ungWv48Bz-pBQUDeXa4iI7ADYaOWF3qctBD_YfIAFa0");
+    }
+}
@@ -0,0 +1,143 @@
+//! hashing to the curve as described in rfc9380, for the suite
+//! `P256_XMD:SHA-256_SSWU_RO_` (`expand_message_xmd` with sha-256, & the
+//! simplified shallue-van de woestijne-ulas map).  the map runs in
+//! constant time, so messages may be secret (as passwords are in a pake).
+//! only built with the `secp256r1` feature.
+use arith::{self,Curve,Limbs,Point,MAX_LIMBS};
+use sha2::{Sha256,Digest};
+
+
+/// size of a field element, & of a sha-256 output.
+const BYTES: usize = 32;
+
+/// size of the sha-256 input block.
+const BLOCK: usize = 64;
+
+/// bytes of uniform output reduced to each field element (`L`, for a
+/// 128-bit security level).
+const L: usize = 48;
+
+/// the map's constant `Z`, negated (`Z = -10`).
+const MINUS_Z: u64 = 10;
+
+/// `(p + 1) / 4`, the exponent of a square root modulo `p = 3 mod 4`.
+const SQRT_EXP: [u64;4] = [0x0000000000000000, 0x0000000040000000, 0x4000000000000000, 0x3FFFFFFFC0000000];
+
+/// prefix for hashing a domain separation tag longer than 255 bytes.
+const OVERSIZE_DST: &[u8] = b"H2C-OVERSIZE-DST-";
+
+
+/// hash `msg` to a uniformly distributed curve point under the domain
+/// separation tag `dst` (rfc9380 §3).
+pub fn hash_to_curve(curve: &Curve, msg: &[u8], dst: &[u8]) -> Point {
+    let uniform = expand_message_xmd(msg,dst,2 * L);
+    let u0 = to_field(curve,&uniform[..L]);
+    let u1 = to_field(curve,&uniform[L..]);
+    // the cofactor is one, so there's nothing to clear.
+    curve.add(&map_to_curve(curve,&u0),&map_to_curve(curve,&u1))
+}
+
+
+/// `expand_message_xmd` with sha-256 (rfc9380 §5.3.1), producing `len`
+/// uniform bytes.  `len` must be at most `255 * BYTES`.
+fn expand_message_xmd(msg: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    debug_assert!(len <= 255 * BYTES);
+    let hashed;
+    let dst = if dst.len() > 255 {
+        hashed = Sha256::new().chain_update(OVERSIZE_DST).chain_update(dst).finalize();
+        &hashed[..]
+    } else {
+        dst
+    };
+    let dst_prime = [dst,&[dst.len() as u8]].concat();
+    let mut hasher = Sha256::new();
+    hasher.update([0u8;BLOCK]);
+    hasher.update(msg);
+    hasher.update((len as u16).to_be_bytes());
+    hasher.update([0x00]);
+    hasher.update(&dst_prime);
+    let b0 = hasher.finalize();
+    let mut out = Vec::with_capacity(len + BYTES);
+    let mut bi = [0u8;BYTES];
+    for i in 1..=len.div_ceil(BYTES) {
+        // `b_1 = H(b_0 || 1 || DST')`, & `b_i = H((b_0 ^ b_(i-1)) || i || DST')`.
+        for (b,b0) in bi.iter_mut().zip(b0.iter()) {
+            *b ^= b0;
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(bi);
+        hasher.update([i as u8]);
+        hasher.update(&dst_prime);
+        bi.copy_from_slice(&hasher.finalize());
+        out.extend_from_slice(&bi);
+    }
+    out.truncate(len);
+    out
+}
+
+
+/// reduce `L` uniform bytes to a field element (montgomery form).
+fn to_field(curve: &Curve, bytes: &[u8]) -> Limbs {
+    let f = &curve.fp;
+    // `hi * 2^256 + lo`, where `2^256 mod p` is montgomery one read as an
+    // integer.
+    let (hi, lo) = bytes.split_at(L - BYTES);
+    let shift = f.mont_form(&f.one());
+    f.add(&f.mul(&f.mont_form(&arith::from_be(hi)),&shift),&f.mont_form(&arith::from_be(lo)))
+}
+
+
+/// the simplified swu map (rfc9380 §6.6.2) of a field element (montgomery
+/// form), without branching on it.
+fn map_to_curve(curve: &Curve, u: &Limbs) -> Point {
+    let f = &curve.fp;
+    let (a, b) = curve.coefficients();
+    let mut minus_z = [0u64;MAX_LIMBS];
+    minus_z[0] = MINUS_Z;
+    let z = f.neg(&f.mont_form(&minus_z));
+    let rhs = |x: &Limbs| f.add(&f.mul(&f.add(&f.mul(x,x),&a),x),&b);
+    // `tv1 = 1 / (Z^2 u^4 + Z u^2)`, or zero if the denominator is.
+    let zu2 = f.mul(&z,&f.mul(u,u));
+    let tv1 = f.inv(&f.add(&f.mul(&zu2,&zu2),&zu2));
+    // `x1 = (-B / A) (1 + tv1)`, or `B / (Z A)` if `tv1` is zero.
+    let x1 = f.mul(&f.mul(&f.neg(&b),&f.inv(&a)),&f.add(&f.one(),&tv1));
+    let x1 = arith::select(&x1,&f.mul(&b,&f.inv(&f.mul(&z,&a))),mask(arith::is_zero(&tv1)));
+    let x2 = f.mul(&zu2,&x1);
+    let (gx1, gx2) = (rhs(&x1), rhs(&x2));
+    // exactly one of `gx1` & `gx2` is square.
+    let mut exp = [0u64;MAX_LIMBS];
+    exp[..SQRT_EXP.len()].copy_from_slice(&SQRT_EXP);
+    let y1 = f.pow(&gx1,&exp);
+    let square = mask(eq(&f.mul(&y1,&y1),&gx1));
+    let x = arith::select(&x2,&x1,square);
+    let y = arith::select(&f.pow(&gx2,&exp),&y1,square);
+    // match the sign (parity) of `y` to that of `u`.
+    let flip = mask((f.canonical(u)[0] ^ f.canonical(&y)[0]) & 1 == 1);
+    let y = arith::select(&y,&f.neg(&y),flip);
+    curve.affine_point(&x,&y).expect("the swu map lands on the curve")
+}
+
+
+/// an all-ones mask if `bit` is set, or all zeroes otherwise.
+fn mask(bit: bool) -> u64 { (bit as u64).wrapping_neg() }
+
+
+/// compare limbs without branching on them.
+fn eq(a: &Limbs, b: &Limbs) -> bool {
+    a.iter().zip(b.iter()).fold(0, |acc,(a,b)| acc | (a ^ b)) == 0
+}
+
+
+#[cfg(test)]
+mod tests {
+    use h2c::expand_message_xmd;
+    use hex::unhex;
+
+    #[test]
+    fn expand_message_xmd_ok() {
+        // from rfc9380 appendix k.1.
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        assert_eq!(expand_message_xmd(b"",dst,0x20),unhex("68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"));
+        assert_eq!(expand_message_xmd(b"abc",dst,0x20),unhex("d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"));
+    }
+}
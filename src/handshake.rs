@@ -0,0 +1,238 @@
+//! an x3dh-style key agreement (after signal's "extended triple
+//! diffie-hellman") over `secp256r1` keys, for establishing a session
+//! secret with a party who may be offline.  only built with the
+//! `secp256r1` feature.
+//!
+//! the responder publishes a `PrekeyBundle`: its long-term identity key, a
+//! medium-term prekey signed by the identity key, & optionally a one-time
+//! prekey.  the initiator checks the bundle & runs `initiate`, sending the
+//! resulting `InitialMessage` (its identity & ephemeral keys) alongside its
+//! first ciphertext; the responder runs `respond` with the matching secret
+//! keys to arrive at the same `Session`.  a one-time prekey must be
+//! discarded once used, or the handshake loses its replay protection.
+//!
+//! identity keys both sign (ecdsa over the prekey) & take part in ecdh; the
+//! signature is made over a tagged message, so that it can't be mistaken
+//! for a signature made with the same key elsewhere.
+use secp256r1::{Public,Signature,SharedSecret,KeyPair,ecdh,sign_message,verify_message};
+use error::Error;
+use arith;
+use hkdf;
+
+
+/// size of a session secret.
+pub const SESSION_SECRET_SIZE: usize = hkdf::KEY_LEN;
+
+/// prefix of the message signed by a bundle's identity key.
+const PREKEY_TAG: &[u8] = b"easy-ecc/x3dh/prekey";
+
+/// hkdf `info` for the session secret.
+const INFO: &[u8] = b"easy-ecc/x3dh/secp256r1";
+
+/// size of curve.
+const BYTES: usize = 32;
+
+
+/// the secret shared by the two parties of a handshake.
+pub struct SessionSecret([u8;SESSION_SECRET_SIZE]);
+impl_newtype_bytearray!(SessionSecret,SESSION_SECRET_SIZE);
+impl_redacted_fmt!(SessionSecret);
+impl_ct_eq!(SessionSecret);
+impl_serde_bytearray!(SessionSecret,SESSION_SECRET_SIZE);
+impl_zeroize!(SessionSecret);
+
+
+/// a completed handshake: the session secret, along with the associated
+/// data (both identity keys, initiator first) which the first ciphertext
+/// should authenticate, binding the session to the parties' identities.
+#[derive(Debug,PartialEq,Eq)]
+pub struct Session {
+    secret: SessionSecret,
+    associated_data: Vec<u8>,
+}
+
+impl Session {
+    /// the session secret.
+    pub fn secret(&self) -> &SessionSecret { &self.secret }
+
+    /// the associated data, `initiator identity || responder identity`.
+    pub fn associated_data(&self) -> &[u8] { &self.associated_data }
+}
+
+
+/// the keys a responder publishes so that others can begin handshakes with
+/// it.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct PrekeyBundle {
+    /// the long-term identity key.
+    pub identity: Public,
+    /// the signed prekey.
+    pub prekey: Public,
+    /// the identity key's signature over the prekey.
+    pub signature: Signature,
+    /// a one-time prekey, if any remain.
+    pub one_time_prekey: Option<Public>,
+}
+
+impl PrekeyBundle {
+    /// sign `prekey` with `identity`, publishing the optional
+    /// `one_time_prekey` alongside it.
+    pub fn new(identity: &KeyPair, prekey: &Public, one_time_prekey: Option<&Public>) -> Result<Self,Error> {
+        Ok(PrekeyBundle {
+            identity: *identity.public(),
+            prekey: *prekey,
+            signature: sign_message(identity.secret(),&prekey_message(prekey))?,
+            one_time_prekey: one_time_prekey.copied(),
+        })
+    }
+
+    /// check the prekey's signature, failing with `Error::InvalidSignature`
+    /// if it wasn't made by the identity key.
+    pub fn verify(&self) -> Result<(),Error> {
+        verify_message(&self.identity,&prekey_message(&self.prekey),&self.signature)
+    }
+}
+
+
+/// the initiator's keys, sent to the responder with the first ciphertext.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct InitialMessage {
+    /// the initiator's identity key.
+    pub identity: Public,
+    /// the initiator's ephemeral key.
+    pub ephemeral: Public,
+    /// the responder's one-time prekey which was used, if any.
+    pub one_time_prekey: Option<Public>,
+}
+
+
+/// begin a handshake with the owner of `bundle` as `identity`, checking the
+/// bundle's signature first.  returns the session along with the message
+/// to send to the responder.
+pub fn initiate(identity: &KeyPair, bundle: &PrekeyBundle) -> Result<(Session,InitialMessage),Error> {
+    initiate_with(identity,&KeyPair::generate()?,bundle)
+}
+
+
+/// begin a handshake under the given ephemeral keypair, which mustn't be
+/// used again.
+fn initiate_with(identity: &KeyPair, ephemeral: &KeyPair, bundle: &PrekeyBundle) -> Result<(Session,InitialMessage),Error> {
+    bundle.verify()?;
+    let mut secrets = vec![
+        ecdh(&bundle.prekey,identity.secret())?,
+        ecdh(&bundle.identity,ephemeral.secret())?,
+        ecdh(&bundle.prekey,ephemeral.secret())?,
+    ];
+    if let Some(ref key) = bundle.one_time_prekey {
+        secrets.push(ecdh(key,ephemeral.secret())?);
+    }
+    let session = session(&secrets,identity.public(),&bundle.identity)?;
+    let msg = InitialMessage {
+        identity: *identity.public(),
+        ephemeral: *ephemeral.public(),
+        one_time_prekey: bundle.one_time_prekey,
+    };
+    Ok((session,msg))
+}
+
+
+/// complete a handshake begun by `msg`, as the owner of the `identity`,
+/// `prekey` & (if `msg` names one) `one_time_prekey` of the bundle it was
+/// begun with.  a `one_time_prekey` which doesn't match the one named by
+/// `msg` yields `Error::InvalidKey`.
+pub fn respond(identity: &KeyPair, prekey: &KeyPair, one_time_prekey: Option<&KeyPair>, msg: &InitialMessage) -> Result<Session,Error> {
+    let mut secrets = vec![
+        ecdh(&msg.identity,prekey.secret())?,
+        ecdh(&msg.ephemeral,identity.secret())?,
+        ecdh(&msg.ephemeral,prekey.secret())?,
+    ];
+    match (one_time_prekey, &msg.one_time_prekey) {
+        (Some(pair), Some(key)) if pair.public() == key => secrets.push(ecdh(&msg.ephemeral,pair.secret())?),
+        (None, None) => { },
+        _ => return Err(Error::InvalidKey),
+    }
+    session(&secrets,&msg.identity,identity.public())
+}
+
+
+/// derive the session from the ecdh outputs: hkdf over `F || DH1 || ...`,
+/// where `F` is a block of `0xff` bytes (as x3dh prescribes).
+fn session(secrets: &[SharedSecret], initiator: &Public, responder: &Public) -> Result<Session,Error> {
+    let mut ikm = vec![0xff;BYTES];
+    for secret in secrets {
+        ikm.extend_from_slice(secret.expose_secret());
+    }
+    let mut session = Session {
+        secret: SessionSecret([0u8;SESSION_SECRET_SIZE]),
+        associated_data: [initiator.as_ref(),responder.as_ref()].concat(),
+    };
+    let rslt = hkdf::derive(&[],&ikm,INFO,&mut session.secret.0);
+    arith::wipe_bytes(&mut ikm);
+    rslt.map(|()| session)
+}
+
+
+/// the message signed by a bundle's identity key.
+fn prekey_message(prekey: &Public) -> Vec<u8> {
+    [PREKEY_TAG,prekey.as_ref()].concat()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use handshake::{PrekeyBundle,InitialMessage,initiate,initiate_with,respond};
+    use secp256r1::KeyPair;
+    use error::Error;
+
+    #[test]
+    fn handshake_ok() {
+        let (alice, bob) = (KeyPair::generate().unwrap(), KeyPair::generate().unwrap());
+        let prekey = KeyPair::generate().unwrap();
+        let one_time = KeyPair::generate().unwrap();
+        // with & without a one-time prekey.
+        for one_time in &[Some(&one_time),None] {
+            let bundle = PrekeyBundle::new(&bob,prekey.public(),one_time.map(|pair| pair.public())).unwrap();
+            let (session, msg) = initiate(&alice,&bundle).unwrap();
+            assert_eq!(respond(&bob,&prekey,*one_time,&msg).unwrap(),session);
+            assert_eq!(session.associated_data(),&[alice.public().as_ref(),bob.public().as_ref()].concat()[..]);
+        }
+        // a fresh ephemeral key gives a fresh session.
+        let bundle = PrekeyBundle::new(&bob,prekey.public(),None).unwrap();
+        let ephemeral = KeyPair::generate().unwrap();
+        let (first, _) = initiate_with(&alice,&ephemeral,&bundle).unwrap();
+        let (again, _) = initiate_with(&alice,&ephemeral,&bundle).unwrap();
+        let (other, _) = initiate(&alice,&bundle).unwrap();
+        assert_eq!(first,again);
+        assert!(first.secret() != other.secret());
+    }
+
+    #[test]
+    fn handshake_err() {
+        let (alice, bob, mallory) = (KeyPair::generate().unwrap(), KeyPair::generate().unwrap(), KeyPair::generate().unwrap());
+        let prekey = KeyPair::generate().unwrap();
+        let one_time = KeyPair::generate().unwrap();
+        // a prekey which wasn't signed by the identity key.
+        let mut bundle = PrekeyBundle::new(&mallory,prekey.public(),None).unwrap();
+        bundle.identity = *bob.public();
+        assert_eq!(initiate(&alice,&bundle).err(),Some(Error::InvalidSignature));
+        // the responder must supply exactly the one-time prekey which was used.
+        let bundle = PrekeyBundle::new(&bob,prekey.public(),Some(one_time.public())).unwrap();
+        let (session, msg) = initiate(&alice,&bundle).unwrap();
+        assert_eq!(respond(&bob,&prekey,None,&msg).err(),Some(Error::InvalidKey));
+        assert_eq!(respond(&bob,&prekey,Some(&prekey),&msg).err(),Some(Error::InvalidKey));
+        // an impostor claiming alice's identity can't compute the session
+        // bob arrives at.
+        let bundle = PrekeyBundle::new(&bob,prekey.public(),None).unwrap();
+        let ephemeral = KeyPair::generate().unwrap();
+        let (impostor, _) = initiate_with(&mallory,&ephemeral,&bundle).unwrap();
+        let forged = InitialMessage {
+            identity: *alice.public(),
+            ephemeral: *ephemeral.public(),
+            one_time_prekey: None,
+        };
+        assert!(respond(&bob,&prekey,None,&forged).unwrap().secret() != impostor.secret());
+        assert!(session.secret() != impostor.secret());
+    }
+}
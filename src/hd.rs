@@ -0,0 +1,486 @@
+//! bip-32 hierarchical deterministic keys over `secp256k1`.
+//!
+//! an `XPrv` is a secret key together with a chain code, from which any
+//! number of child keys can be derived; its `XPub` can derive the public
+//! halves of the non-hardened children without ever seeing a secret.  keys
+//! are addressed by `DerivationPath`s such as `m/44'/0'/0'/0/1`, in which a
+//! trailing `'` marks a hardened index, & serialize to the standard
+//! base58check `xprv...` & `xpub...` strings.
+use std::fmt;
+use std::str::FromStr;
+use hmac::{Hmac,Mac};
+use sha2::{Sha256,Sha512,Digest};
+use ripemd::Ripemd160;
+use arith;
+use secp256k1::{Public,Secret};
+use base58;
+use error::Error;
+
+
+type HmacSha512 = Hmac<Sha512>;
+
+
+/// size of curve.
+const BYTES: usize = 32;
+
+
+/// the first hardened child index; indices from here up can only be
+/// derived from an `XPrv`.
+pub const HARDENED: u32 = 1 << 31;
+
+/// size of a chain code.
+pub const CHAIN_CODE_SIZE: usize = 32;
+
+/// size of a key fingerprint.
+pub const FINGERPRINT_SIZE: usize = 4;
+
+/// smallest seed accepted by `XPrv::from_seed`.
+pub const MIN_SEED_SIZE: usize = 16;
+
+/// largest seed accepted by `XPrv::from_seed`.
+pub const MAX_SEED_SIZE: usize = 64;
+
+/// hmac key used to derive a master key from a seed.
+const SEED_KEY: &[u8] = b"Bitcoin seed";
+
+/// version prefixes of serialized mainnet extended keys.
+const XPRV_VERSION: [u8;4] = [0x04, 0x88, 0xad, 0xe4];
+const XPUB_VERSION: [u8;4] = [0x04, 0x88, 0xb2, 0x1e];
+
+/// size of a serialized extended key (before base58check coding).
+const ENCODED_SIZE: usize = 78;
+
+
+/// an extended secret key.  its `Debug` output is redacted, since anyone
+/// who sees it holds every key derived from it.
+#[derive(PartialEq,Eq)]
+pub struct XPrv {
+    secret: Secret,
+    public: Public,
+    chain_code: [u8;CHAIN_CODE_SIZE],
+    depth: u8,
+    parent_fingerprint: [u8;FINGERPRINT_SIZE],
+    child_number: u32,
+}
+
+impl XPrv {
+    /// derive the master key from `seed` (such as a bip-39 seed), which
+    /// must be 16 to 64 bytes long.  a seed of any other length, or one
+    /// which yields an invalid key, gives `Error::InvalidDerivation`.
+    pub fn from_seed(seed: &[u8]) -> Result<Self,Error> {
+        if seed.len() < MIN_SEED_SIZE || seed.len() > MAX_SEED_SIZE {
+            return Err(Error::InvalidDerivation);
+        }
+        let mut i = hmac(SEED_KEY,&[seed]);
+        let rslt = XPrv::from_parts(&i,0,[0u8;FINGERPRINT_SIZE],0);
+        arith::wipe_bytes(&mut i);
+        rslt
+    }
+
+    /// derive the child key at `index` (hardened if at least `HARDENED`).
+    /// a child beyond depth 255, or one which lands on an invalid key
+    /// (with probability below `2^-127`; bip-32 moves on to the next index),
+    /// gives `Error::InvalidDerivation`.
+    pub fn derive_child(&self, index: u32) -> Result<Self,Error> {
+        let depth = self.depth.checked_add(1).ok_or(Error::InvalidDerivation)?;
+        let mut data = [0u8;BYTES+1];
+        if index >= HARDENED {
+            data[1..].copy_from_slice(self.secret.as_ref());
+        } else {
+            data.copy_from_slice(self.public.as_ref());
+        }
+        let mut i = hmac(&self.chain_code,&[&data,&index.to_be_bytes()]);
+        arith::wipe_bytes(&mut data);
+        // the child's secret is `k + IL mod n`.
+        let mut il = [0u8;BYTES];
+        il.copy_from_slice(&i[..BYTES]);
+        let rslt = self.secret.tweak_add(&il).map_err(|_| Error::InvalidDerivation).and_then(|child| {
+            i[..BYTES].copy_from_slice(child.as_ref());
+            XPrv::from_parts(&i,depth,self.fingerprint(),index)
+        });
+        arith::wipe_bytes(&mut il);
+        arith::wipe_bytes(&mut i);
+        rslt
+    }
+
+    /// derive the key at `path`, relative to this one.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self,Error> {
+        let mut bytes = self.to_bytes();
+        let key = XPrv::from_parts(&bytes,self.depth,self.parent_fingerprint,self.child_number);
+        arith::wipe_bytes(&mut bytes);
+        let mut key = key?;
+        for index in path.indices() {
+            key = key.derive_child(*index)?;
+        }
+        Ok(key)
+    }
+
+    /// the extended public key, which can derive this key's non-hardened
+    /// public children.
+    pub fn to_xpub(&self) -> XPub {
+        XPub {
+            public: self.public,
+            chain_code: self.chain_code,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+        }
+    }
+
+    /// the secret key.
+    pub fn secret(&self) -> &Secret { &self.secret }
+
+    /// the public key.
+    pub fn public(&self) -> &Public { &self.public }
+
+    /// the chain code.
+    pub fn chain_code(&self) -> &[u8;CHAIN_CODE_SIZE] { &self.chain_code }
+
+    /// the number of derivations from the master key (which has depth 0).
+    pub fn depth(&self) -> u8 { self.depth }
+
+    /// the index this key was derived at (0 for the master key).
+    pub fn child_number(&self) -> u32 { self.child_number }
+
+    /// the fingerprint of the parent key (all zeros for the master key).
+    pub fn parent_fingerprint(&self) -> [u8;FINGERPRINT_SIZE] { self.parent_fingerprint }
+
+    /// this key's fingerprint: the first four bytes of the hash160 of its
+    /// public key.
+    pub fn fingerprint(&self) -> [u8;FINGERPRINT_SIZE] { fingerprint(self.public.as_ref()) }
+
+    /// serialize as a base58check `xprv...` string.
+    pub fn to_base58(&self) -> String {
+        let mut key = [0u8;BYTES+1];
+        key[1..].copy_from_slice(self.secret.as_ref());
+        let mut bytes = encode(&XPRV_VERSION,self.depth,&self.parent_fingerprint,self.child_number,&self.chain_code,&key);
+        let text = base58::encode_check(&bytes);
+        arith::wipe_bytes(&mut key);
+        arith::wipe_bytes(&mut bytes);
+        text
+    }
+
+    /// parse a base58check `xprv...` string.  a malformed string, or one
+    /// for another kind of key, yields `Error::MalformedEncoding`, & an
+    /// out-of-range secret yields `Error::InvalidSecretKey`.
+    pub fn from_base58(text: &str) -> Result<Self,Error> {
+        let mut bytes = base58::decode_check(text)?;
+        let rslt = decode(&bytes,&XPRV_VERSION).and_then(|(depth, parent, child)| {
+            if bytes[45] != 0x00 { return Err(Error::MalformedEncoding); }
+            let mut i = [0u8;BYTES+CHAIN_CODE_SIZE];
+            i[..BYTES].copy_from_slice(&bytes[46..]);
+            i[BYTES..].copy_from_slice(&bytes[13..45]);
+            let rslt = XPrv::from_parts(&i,depth,parent,child).map_err(|_| Error::InvalidSecretKey);
+            arith::wipe_bytes(&mut i);
+            rslt
+        });
+        arith::wipe_bytes(&mut bytes);
+        rslt
+    }
+
+    /// build a key from `i`, the secret followed by the chain code.
+    fn from_parts(i: &[u8], depth: u8, parent_fingerprint: [u8;FINGERPRINT_SIZE], child_number: u32) -> Result<Self,Error> {
+        let mut bytes = [0u8;BYTES];
+        bytes.copy_from_slice(&i[..BYTES]);
+        let secret = Secret::try_from_bytes(&bytes);
+        arith::wipe_bytes(&mut bytes);
+        let secret = secret.map_err(|_| Error::InvalidDerivation)?;
+        let public = secret.public()?;
+        let mut chain_code = [0u8;CHAIN_CODE_SIZE];
+        chain_code.copy_from_slice(&i[BYTES..]);
+        Ok(XPrv { secret, public, chain_code, depth, parent_fingerprint, child_number })
+    }
+
+    /// the secret followed by the chain code.
+    fn to_bytes(&self) -> [u8;BYTES+CHAIN_CODE_SIZE] {
+        let mut bytes = [0u8;BYTES+CHAIN_CODE_SIZE];
+        bytes[..BYTES].copy_from_slice(self.secret.as_ref());
+        bytes[BYTES..].copy_from_slice(&self.chain_code);
+        bytes
+    }
+}
+
+impl fmt::Debug for XPrv {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("XPrv([redacted])") }
+}
+
+impl Drop for XPrv {
+    fn drop(&mut self) { arith::wipe_bytes(&mut self.chain_code); }
+}
+
+
+/// an extended public key.
+#[derive(Debug,PartialEq,Eq)]
+pub struct XPub {
+    public: Public,
+    chain_code: [u8;CHAIN_CODE_SIZE],
+    depth: u8,
+    parent_fingerprint: [u8;FINGERPRINT_SIZE],
+    child_number: u32,
+}
+
+impl XPub {
+    /// derive the public child key at `index`, which must not be hardened
+    /// (or `Error::InvalidDerivation` results, as it does for a child
+    /// beyond depth 255 or one which lands on the point at infinity).
+    pub fn derive_child(&self, index: u32) -> Result<Self,Error> {
+        if index >= HARDENED {
+            return Err(Error::InvalidDerivation);
+        }
+        let depth = self.depth.checked_add(1).ok_or(Error::InvalidDerivation)?;
+        self.public.validate()?;
+        let i = hmac(&self.chain_code,&[self.public.as_ref(),&index.to_be_bytes()]);
+        // the child is `K + IL*G`.
+        let mut il = [0u8;BYTES];
+        il.copy_from_slice(&i[..BYTES]);
+        let public = self.public.tweak_add(&il).map_err(|_| Error::InvalidDerivation)?;
+        let mut chain_code = [0u8;CHAIN_CODE_SIZE];
+        chain_code.copy_from_slice(&i[BYTES..]);
+        Ok(XPub { public, chain_code, depth, parent_fingerprint: self.fingerprint(), child_number: index })
+    }
+
+    /// derive the key at `path`, relative to this one.  a path with a
+    /// hardened index yields `Error::InvalidDerivation`.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self,Error> {
+        let mut key = XPub {
+            public: self.public,
+            chain_code: self.chain_code,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+        };
+        for index in path.indices() {
+            key = key.derive_child(*index)?;
+        }
+        Ok(key)
+    }
+
+    /// the public key.
+    pub fn public(&self) -> &Public { &self.public }
+
+    /// the chain code.
+    pub fn chain_code(&self) -> &[u8;CHAIN_CODE_SIZE] { &self.chain_code }
+
+    /// the number of derivations from the master key (which has depth 0).
+    pub fn depth(&self) -> u8 { self.depth }
+
+    /// the index this key was derived at (0 for the master key).
+    pub fn child_number(&self) -> u32 { self.child_number }
+
+    /// the fingerprint of the parent key (all zeros for the master key).
+    pub fn parent_fingerprint(&self) -> [u8;FINGERPRINT_SIZE] { self.parent_fingerprint }
+
+    /// this key's fingerprint: the first four bytes of the hash160 of its
+    /// public key.
+    pub fn fingerprint(&self) -> [u8;FINGERPRINT_SIZE] { fingerprint(self.public.as_ref()) }
+
+    /// serialize as a base58check `xpub...` string.
+    pub fn to_base58(&self) -> String {
+        base58::encode_check(&encode(&XPUB_VERSION,self.depth,&self.parent_fingerprint,self.child_number,&self.chain_code,self.public.as_bytes()))
+    }
+
+    /// parse a base58check `xpub...` string.  a malformed string, or one
+    /// for another kind of key, yields `Error::MalformedEncoding`, & a
+    /// point not on the curve yields `Error::InvalidKey`.
+    pub fn from_base58(text: &str) -> Result<Self,Error> {
+        let bytes = base58::decode_check(text)?;
+        let (depth, parent_fingerprint, child_number) = decode(&bytes,&XPUB_VERSION)?;
+        let mut public = [0u8;BYTES+1];
+        public.copy_from_slice(&bytes[45..]);
+        let public = Public::try_from_bytes(&public).map_err(|_| Error::InvalidKey)?;
+        let mut chain_code = [0u8;CHAIN_CODE_SIZE];
+        chain_code.copy_from_slice(&bytes[13..45]);
+        Ok(XPub { public, chain_code, depth, parent_fingerprint, child_number })
+    }
+}
+
+
+/// a sequence of child indices, leading from a key to one of its
+/// descendants.  written as `m/44'/0'/0'/0/1`, where `m` stands for the
+/// starting key & a trailing `'` (or `h`) marks a hardened index.
+#[derive(Debug,Clone,PartialEq,Eq,Default)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// the child indices, from the starting key downwards.
+    pub fn indices(&self) -> &[u32] { &self.0 }
+}
+
+impl From<Vec<u32>> for DerivationPath {
+    fn from(indices: Vec<u32>) -> Self { DerivationPath(indices) }
+}
+
+impl FromStr for DerivationPath {
+    type Err = Error;
+
+    /// parse a path such as `m/44'/0'/0'/0/1`.  anything else (including an
+    /// index of `2^31` or more, which must be written as hardened) yields
+    /// `Error::MalformedEncoding`.
+    fn from_str(text: &str) -> Result<Self,Error> {
+        let mut parts = text.split('/');
+        if parts.next() != Some("m") {
+            return Err(Error::MalformedEncoding);
+        }
+        let mut indices = Vec::new();
+        for part in parts {
+            let (digits, hardened) = match part.strip_suffix(['\'','h','H']) {
+                Some(digits) => (digits, true),
+                None => (part, false),
+            };
+            if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+                return Err(Error::MalformedEncoding);
+            }
+            let index: u32 = digits.parse().map_err(|_| Error::MalformedEncoding)?;
+            if index >= HARDENED {
+                return Err(Error::MalformedEncoding);
+            }
+            indices.push(if hardened { index | HARDENED } else { index });
+        }
+        Ok(DerivationPath(indices))
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("m")?;
+        for index in &self.0 {
+            if *index >= HARDENED { write!(f,"/{}'",index & !HARDENED)?; } else { write!(f,"/{}",index)?; }
+        }
+        Ok(())
+    }
+}
+
+
+/// compute `HMAC-SHA512_key(parts[0] || parts[1] || ...)`.
+pub(crate) fn hmac(key: &[u8], parts: &[&[u8]]) -> [u8;BYTES+CHAIN_CODE_SIZE] {
+    let mut mac = HmacSha512::new_from_slice(key).expect("hmac accepts keys of any length");
+    for part in parts {
+        mac.update(part);
+    }
+    let mut out = [0u8;BYTES+CHAIN_CODE_SIZE];
+    out.copy_from_slice(&mac.finalize().into_bytes());
+    out
+}
+
+
+/// the first four bytes of `ripemd160(sha256(public))`, for a compressed
+/// public key.
+pub(crate) fn fingerprint(public: &[u8]) -> [u8;FINGERPRINT_SIZE] {
+    let hash = Ripemd160::digest(Sha256::digest(public));
+    let mut out = [0u8;FINGERPRINT_SIZE];
+    out.copy_from_slice(&hash[..FINGERPRINT_SIZE]);
+    out
+}
+
+
+/// serialize an extended key.
+fn encode(version: &[u8;4], depth: u8, parent: &[u8;FINGERPRINT_SIZE], child: u32, chain_code: &[u8;CHAIN_CODE_SIZE], key: &[u8;BYTES+1]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(ENCODED_SIZE);
+    bytes.extend_from_slice(version);
+    bytes.push(depth);
+    bytes.extend_from_slice(parent);
+    bytes.extend_from_slice(&child.to_be_bytes());
+    bytes.extend_from_slice(chain_code);
+    bytes.extend_from_slice(key);
+    bytes
+}
+
+
+/// check a serialized extended key against the expected `version`, & split
+/// out its depth, parent fingerprint & child number (the chain code & key
+/// follow, at `bytes[13..45]` & `bytes[45..]`).  a master key must have a
+/// zero parent fingerprint & child number.
+fn decode(bytes: &[u8], version: &[u8;4]) -> Result<(u8,[u8;FINGERPRINT_SIZE],u32),Error> {
+    if bytes.len() != ENCODED_SIZE || bytes[..4] != version[..] {
+        return Err(Error::MalformedEncoding);
+    }
+    let depth = bytes[4];
+    let mut parent = [0u8;FINGERPRINT_SIZE];
+    parent.copy_from_slice(&bytes[5..9]);
+    let child = u32::from_be_bytes([bytes[9], bytes[10], bytes[11], bytes[12]]);
+    if depth == 0 && (parent != [0u8;FINGERPRINT_SIZE] || child != 0) {
+        return Err(Error::MalformedEncoding);
+    }
+    Ok((depth,parent,child))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use hd::{XPrv,XPub,DerivationPath,HARDENED};
+    use error::Error;
+    use hex::unhex;
+
+    // bip-32 test vector 1.
+    const SEED: &str = "000102030405060708090a0b0c0d0e0f";
+    const VECTORS: &[(&str,&str,&str)] = &[
+        ("m",
+         "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi",
+         "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"),
+        ("m/0'",
+         "xprv9uHRZZhk6KAJC1avXpDAp4MDc3sQKNxDiPvvkX8Br5ngLNv1TxvUxt4cV1rGL5hj6KCesnDYUhd7oWgT11eZG7XnxHrnYeSvkzY7d2bhkJ7",
+         "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw"),
+        ("m/0'/1",
+         "xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs",
+         "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ"),
+        ("m/0'/1/2'",
+         "xprv9z4pot5VBttmtdRTWfWQmoH1taj2axGVzFqSb8C9xaxKymcFzXBDptWmT7FwuEzG3ryjH4ktypQSAewRiNMjANTtpgP4mLTj34bhnZX7UiM",
+         "xpub6D4BDPcP2GT577Vvch3R8wDkScZWzQzMMUm3PWbmWvVJrZwQY4VUNgqFJPMM3No2dFDFGTsxxpG5uJh7n7epu4trkrX7x7DogT5Uv6fcLW5"),
+        ("m/0'/1/2'/2",
+         "xprvA2JDeKCSNNZky6uBCviVfJSKyQ1mDYahRjijr5idH2WwLsEd4Hsb2Tyh8RfQMuPh7f7RtyzTtdrbdqqsunu5Mm3wDvUAKRHSC34sJ7in334",
+         "xpub6FHa3pjLCk84BayeJxFW2SP4XRrFd1JYnxeLeU8EqN3vDfZmbqBqaGJAyiLjTAwm6ZLRQUMv1ZACTj37sR62cfN7fe5JnJ7dh8zL4fiyLHV"),
+        ("m/0'/1/2'/2/1000000000",
+         "xprvA41z7zogVVwxVSgdKUHDy1SKmdb533PjDz7J6N6mV6uS3ze1ai8FHa8kmHScGpWmj4WggLyQjgPie1rFSruoUihUZREPSL39UNdE3BBDu76",
+         "xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy"),
+    ];
+
+    #[test]
+    fn derive_ok() {
+        let master = XPrv::from_seed(&unhex(SEED)).unwrap();
+        for &(path, xprv, xpub) in VECTORS {
+            let path: DerivationPath = path.parse().unwrap();
+            let key = master.derive_path(&path).unwrap();
+            assert_eq!(key.to_base58(),xprv);
+            assert_eq!(key.to_xpub().to_base58(),xpub);
+            assert_eq!(XPrv::from_base58(xprv).unwrap(),key);
+            assert_eq!(XPub::from_base58(xpub).unwrap(),key.to_xpub());
+            assert_eq!(key.depth() as usize,path.indices().len());
+        }
+        // public derivation agrees with private derivation for the
+        // non-hardened steps.
+        let parent = master.derive_path(&"m/0'/1/2'".parse().unwrap()).unwrap();
+        let child = parent.to_xpub().derive_path(&"m/2/1000000000".parse().unwrap()).unwrap();
+        assert_eq!(child.to_base58(),VECTORS[5].2);
+        assert_eq!(child.parent_fingerprint(),parent.derive_child(2).unwrap().fingerprint());
+        assert_eq!(format!("{:?}",master),"XPrv([redacted])");
+    }
+
+    #[test]
+    fn derive_err() {
+        let master = XPrv::from_seed(&unhex(SEED)).unwrap();
+        assert_eq!(master.to_xpub().derive_child(HARDENED).err(),Some(Error::InvalidDerivation));
+        assert_eq!(XPrv::from_seed(&[0u8;15]).err(),Some(Error::InvalidDerivation));
+        assert_eq!(XPrv::from_seed(&[0u8;65]).err(),Some(Error::InvalidDerivation));
+        let mut key = master.derive_child(0).unwrap();
+        for _ in 1..255 { key = key.derive_child(0).unwrap(); }
+        assert_eq!(key.depth(),255);
+        assert_eq!(key.derive_child(0).err(),Some(Error::InvalidDerivation));
+        // an xpub where an xprv is expected (& vice versa), & a corrupted
+        // string.
+        let (xprv, xpub) = (VECTORS[0].1, VECTORS[0].2);
+        assert_eq!(XPrv::from_base58(xpub).err(),Some(Error::MalformedEncoding));
+        assert_eq!(XPub::from_base58(xprv).err(),Some(Error::MalformedEncoding));
+        assert_eq!(XPrv::from_base58(&xprv[..xprv.len()-1]).err(),Some(Error::MalformedEncoding));
+    }
+
+    #[test]
+    fn path_ok() {
+        let path: DerivationPath = "m/44'/0'/0h/0/1".parse().unwrap();
+        assert_eq!(path.indices(),&[44 | HARDENED,HARDENED,HARDENED,0,1]);
+        assert_eq!(path.to_string(),"m/44'/0'/0'/0/1");
+        assert_eq!("m".parse::<DerivationPath>().unwrap(),DerivationPath::default());
+        for bad in &["","44'/0'","m/","m//1","m/-1","m/+1","m/1''","m/2147483648","m/x"] {
+            assert_eq!(bad.parse::<DerivationPath>().err(),Some(Error::MalformedEncoding));
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! hex coding of byte strings.
+use error::Error;
+
+
+/// the lowercase digits.
+const DIGITS: &[u8;16] = b"0123456789abcdef";
+
+
+/// encode `bytes` as lowercase hex.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        out.push(DIGITS[(byte >> 4) as usize] as char);
+        out.push(DIGITS[(byte & 0x0f) as usize] as char);
+    }
+    out
+}
+
+
+/// decode hex of either case (with an optional `0x` prefix) into `out`,
+/// which it must fill exactly.  a character which isn't a hex digit, or an
+/// odd number of digits, yields `Error::MalformedEncoding`, & input of the
+/// wrong length for `out` yields `Error::WrongLength`.
+pub fn decode(text: &str, out: &mut [u8]) -> Result<(),Error> {
+    let text = text.strip_prefix("0x").unwrap_or(text).as_bytes();
+    if !text.len().is_multiple_of(2) {
+        return Err(Error::MalformedEncoding);
+    }
+    if text.len() / 2 != out.len() {
+        return Err(Error::WrongLength { expected: out.len(), got: text.len() / 2 });
+    }
+    for (byte,pair) in out.iter_mut().zip(text.chunks(2)) {
+        *byte = digit(pair[0])? << 4 | digit(pair[1])?;
+    }
+    Ok(())
+}
+
+
+/// decode hex of either case (with an optional `0x` prefix) of any length.
+/// a character which isn't a hex digit, or an odd number of digits, yields
+/// `Error::MalformedEncoding`.
+pub fn decode_vec(text: &str) -> Result<Vec<u8>,Error> {
+    let digits = text.strip_prefix("0x").unwrap_or(text).len();
+    if !digits.is_multiple_of(2) {
+        return Err(Error::MalformedEncoding);
+    }
+    let mut out = vec![0u8;digits / 2];
+    decode(text,&mut out)?;
+    Ok(out)
+}
+
+
+/// decode hex as `decode_vec` does, panicking if it's malformed.  for test
+/// vectors.
+#[cfg(test)]
+pub fn unhex(text: &str) -> Vec<u8> {
+    decode_vec(text).unwrap()
+}
+
+
+/// the value of a hex digit.
+fn digit(c: u8) -> Result<u8,Error> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(Error::MalformedEncoding),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use hex::{encode,decode,decode_vec};
+    use error::Error;
+
+    #[test]
+    fn hex_ok() {
+        assert_eq!(encode(&[0x00,0x7f,0xab,0xff]),"007fabff");
+        assert_eq!(encode(&[]),"");
+        let mut out = [0u8;4];
+        for text in &["007fabff","007FABFF","0x007fABff"] {
+            decode(text,&mut out).unwrap();
+            assert_eq!(out,[0x00,0x7f,0xab,0xff]);
+            assert_eq!(decode_vec(text).unwrap(),out);
+        }
+        assert_eq!(decode_vec("").unwrap(),[]);
+    }
+
+    #[test]
+    fn hex_err() {
+        let mut out = [0u8;2];
+        assert_eq!(decode("abc",&mut out),Err(Error::MalformedEncoding));
+        assert_eq!(decode("abcg",&mut out),Err(Error::MalformedEncoding));
+        assert_eq!(decode("+bcd",&mut out),Err(Error::MalformedEncoding));
+        assert_eq!(decode("abcdef",&mut out),Err(Error::WrongLength { expected: 2, got: 3 }));
+        assert_eq!(decode("0x",&mut out),Err(Error::WrongLength { expected: 2, got: 0 }));
+        assert_eq!(decode_vec("0xabc"),Err(Error::MalformedEncoding));
+        assert_eq!(decode_vec("abcx"),Err(Error::MalformedEncoding));
+    }
+}
@@ -0,0 +1,76 @@
+//! hkdf with hmac-sha256 (rfc5869), for deriving symmetric keys from ecdh
+//! shared secrets.
+use hmac::{Hmac,Mac};
+use sha2::Sha256;
+use error::Error;
+
+
+type HmacSha256 = Hmac<Sha256>;
+
+
+/// size of an hmac-sha256 output.
+const HLEN: usize = 32;
+
+
+/// longest output hkdf can produce (`255` blocks).
+pub const MAX_LEN: usize = 255 * HLEN;
+
+
+/// size of the keys derived by the one-shot `ecdh_hkdf` helpers.
+pub const KEY_LEN: usize = 32;
+
+
+/// derive `okm.len()` bytes of keying material from `ikm`.  an empty `salt`
+/// stands for `HLEN` zero bytes, as in rfc5869 §2.2.  asking for more than
+/// `MAX_LEN` bytes yields `Error::WrongLength`.
+pub fn derive(salt: &[u8], ikm: &[u8], info: &[u8], okm: &mut [u8]) -> Result<(),Error> {
+    if okm.len() > MAX_LEN {
+        return Err(Error::WrongLength { expected: MAX_LEN, got: okm.len() });
+    }
+    let prk = hmac(salt,&[ikm]);
+    let mut block = [0u8;HLEN];
+    for (i,chunk) in okm.chunks_mut(HLEN).enumerate() {
+        let prev = if i == 0 { &[][..] } else { &block[..] };
+        block = hmac(&prk,&[prev,info,&[(i + 1) as u8]]);
+        chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+    Ok(())
+}
+
+
+/// compute `HMAC_key(parts[0] || parts[1] || ...)`.
+fn hmac(key: &[u8], parts: &[&[u8]]) -> [u8;HLEN] {
+    let mut mac = HmacSha256::new_from_slice(key).expect("hmac accepts keys of any length");
+    for part in parts {
+        mac.update(part);
+    }
+    let mut out = [0u8;HLEN];
+    out.copy_from_slice(&mac.finalize().into_bytes());
+    out
+}
+
+
+#[cfg(test)]
+mod tests {
+    use hkdf::{self,MAX_LEN};
+    use error::Error;
+    use hex::unhex;
+
+    #[test]
+    fn rfc5869_ok() {
+        // rfc5869 appendix a, test cases 1 & 3.
+        let ikm = [0x0b;22];
+        let mut okm = [0u8;42];
+        hkdf::derive(&unhex("000102030405060708090a0b0c"),&ikm,&unhex("f0f1f2f3f4f5f6f7f8f9"),&mut okm).unwrap();
+        assert_eq!(&okm[..],&unhex("3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865")[..]);
+        hkdf::derive(&[],&ikm,&[],&mut okm).unwrap();
+        assert_eq!(&okm[..],&unhex("8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8")[..]);
+    }
+
+    #[test]
+    fn length_err() {
+        let mut okm = vec![0u8;MAX_LEN + 1];
+        assert_eq!(hkdf::derive(&[],&[0x01],&[],&mut okm),Err(Error::WrongLength { expected: MAX_LEN, got: MAX_LEN + 1 }));
+        hkdf::derive(&[],&[0x01],&[],&mut okm[..MAX_LEN]).unwrap();
+    }
+}
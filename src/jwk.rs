@@ -0,0 +1,97 @@
+//! json web keys (rfc7517) holding ec keys, for jose & oidc integrations.
+use std::fmt;
+use arith;
+use base64;
+use error::Error;
+
+
+/// key type of an ec jwk.
+const EC: &str = "EC";
+
+
+/// an ec json web key (rfc7518 §6.2).  the coordinates & secret scalar are
+/// big-endian & base64url-encoded without padding.  other members (such as
+/// `kid` or `use`) are ignored when deserializing.  `Debug` output redacts
+/// the secret scalar, & equality compares the public members only, so two
+/// jwks are equal when they describe the same public key.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct Jwk {
+    /// key type (always `"EC"`).
+    pub kty: String,
+    /// curve name (e.g. `"P-256"`).
+    pub crv: String,
+    /// x-coordinate of the public point.
+    pub x: String,
+    /// y-coordinate of the public point.
+    pub y: String,
+    /// the secret scalar, present only in secret keys.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub d: Option<String>,
+}
+
+
+impl Jwk {
+
+    /// build an ec jwk from raw coordinates & an optional secret scalar.
+    pub(crate) fn new(crv: &str, x: &[u8], y: &[u8], d: Option<&[u8]>) -> Self {
+        Jwk {
+            kty: EC.to_owned(),
+            crv: crv.to_owned(),
+            x: encode(x),
+            y: encode(y),
+            d: d.map(encode),
+        }
+    }
+
+    /// check that this is an ec key on the curve named `crv`, failing with
+    /// `Error::InvalidKey` otherwise.
+    pub(crate) fn check(&self, crv: &str) -> Result<(),Error> {
+        if self.kty == EC && self.crv == crv { Ok(()) } else { Err(Error::InvalidKey) }
+    }
+}
+
+
+impl fmt::Debug for Jwk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Jwk")
+            .field("kty",&self.kty)
+            .field("crv",&self.crv)
+            .field("x",&self.x)
+            .field("y",&self.y)
+            .field("d",&self.d.as_ref().map(|_| "[redacted]"))
+            .finish()
+    }
+}
+
+
+impl PartialEq for Jwk {
+    fn eq(&self, other: &Self) -> bool {
+        self.kty == other.kty && self.crv == other.crv && self.x == other.x && self.y == other.y
+    }
+}
+
+
+impl Eq for Jwk { }
+
+
+/// base64url-encode a key component.
+fn encode(bytes: &[u8]) -> String {
+    base64::encode(bytes,base64::URL_SAFE,false)
+}
+
+
+/// base64url-decode a key component, which must be exactly `out.len()` bytes
+/// long (or `Error::MalformedEncoding` results).  the decoded bytes may be a
+/// secret scalar, so the intermediate buffer is wiped.
+pub(crate) fn decode(field: &str, out: &mut [u8]) -> Result<(),Error> {
+    let mut bytes = base64::decode(field,base64::URL_SAFE,false)?;
+    let rslt = if bytes.len() == out.len() {
+        out.copy_from_slice(&bytes);
+        Ok(())
+    } else {
+        Err(Error::MalformedEncoding)
+    };
+    arith::wipe_bytes(&mut bytes);
+    rslt
+}
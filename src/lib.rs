@@ -1,9 +1,124 @@
 //! A Rust wrapper around the `easy-ecc` C library.
 #![warn(missing_docs)]
+// the shared machinery is only used by the weierstrass curve modules.
+#![cfg_attr(not(any(feature = "brainpoolp256r1", feature = "secp128r1", feature = "secp192r1", feature = "secp224r1", feature = "secp256k1", feature = "secp256r1", feature = "secp384r1", feature = "secp521r1")), allow(dead_code, unused_macros))]
 
-#[macro_use]
-extern crate serde_hex;
 extern crate libc;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_derive;
+extern crate sha2;
+extern crate hmac;
+extern crate aes_gcm;
+extern crate getrandom;
+extern crate ripemd;
+extern crate subtle;
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+#[cfg(feature = "defmt")]
+extern crate defmt;
+#[cfg(feature = "rand_core")]
+extern crate rand_core;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "signature")]
+extern crate signature;
+#[cfg(feature = "zeroize")]
+extern crate zeroize;
+#[cfg(test)]
+extern crate bincode;
+#[cfg(test)]
+extern crate serde_cbor;
+#[cfg(test)]
+extern crate serde_json;
+#[macro_use]
+mod macros;
+mod arith;
+#[cfg(any(feature = "secp256k1", all(feature = "encoding", any(feature = "secp256k1", feature = "secp256r1", feature = "secp384r1", feature = "secp521r1"))))]
+mod base58;
+mod base64;
+#[cfg(feature = "encoding")]
+mod bech32;
+#[cfg(feature = "secp256k1")]
+pub mod blind;
+#[cfg(feature = "brainpoolp256r1")]
+pub mod brainpoolp256r1;
+#[cfg(feature = "secp256r1")]
+pub mod channel;
+#[cfg(any(feature = "secp256k1", feature = "secp256r1", feature = "secp384r1", feature = "secp521r1"))]
+mod cose;
+pub mod curve;
+mod der;
+mod drbg;
+mod ecdsa;
+mod ecies;
+#[cfg(feature = "ed25519")]
+pub mod ed25519;
+pub mod envelope;
+mod error;
+pub mod fingerprint;
+#[cfg(feature = "secp256r1")]
+mod h2c;
+#[cfg(feature = "secp256r1")]
+pub mod handshake;
+#[cfg(feature = "secp256k1")]
+pub mod hd;
+mod hex;
+mod hkdf;
+pub mod jwk;
+#[cfg(feature = "mnemonic")]
+pub mod mnemonic;
+#[cfg(all(feature = "encoding", any(feature = "secp256k1", feature = "secp256r1", feature = "secp384r1", feature = "secp521r1")))]
+mod multibase;
+#[cfg(feature = "secp256k1")]
+pub mod musig;
+#[cfg(feature = "secp256r1")]
+pub mod noise;
+#[cfg(feature = "pem")]
+mod pem;
+mod portable;
+mod random;
+#[cfg(feature = "secp256r1")]
+mod rfc6979;
+#[cfg(feature = "secp256k1")]
+pub mod schnorr;
+#[cfg(feature = "secp128r1")]
+pub mod secp128r1;
+#[cfg(feature = "secp192r1")]
+pub mod secp192r1;
+#[cfg(feature = "secp224r1")]
+pub mod secp224r1;
+#[cfg(feature = "secp256k1")]
+pub mod secp256k1;
+#[cfg(feature = "secp256r1")]
 pub mod secp256r1;
+#[cfg(feature = "secp384r1")]
+pub mod secp384r1;
+#[cfg(feature = "secp521r1")]
+pub mod secp521r1;
+#[cfg(feature = "serde")]
+pub mod serialize;
+#[cfg(all(feature = "secp256r1", feature = "secp256k1"))]
+pub mod slip10;
+#[cfg(any(feature = "secp256r1", feature = "secp384r1", feature = "secp521r1"))]
+mod ssh;
+#[cfg(feature = "secp256k1")]
+pub mod threshold;
+#[cfg(feature = "secp256r1")]
+mod vrf;
+pub mod x25519;
+
+#[cfg(feature = "encoding")]
+pub use base64::Base64;
+pub use curve::{Curve,Parity};
+pub use envelope::{KeyEnvelope,EnvelopeKey};
+pub use error::{Error,BatchError};
+pub use fingerprint::{Fingerprint,KeyId};
+pub use jwk::Jwk;
+pub use random::rng_selftest;
+#[cfg(feature = "custom-rng")]
+pub use random::{EntropySource,set_entropy_source};
 
 
@@ -0,0 +1,211 @@
+//! bip-39 mnemonic phrases, for backing up & restoring keys by hand.  only
+//! built with the `mnemonic` feature.
+//!
+//! a `Mnemonic` encodes 128 to 256 bits of entropy (plus a checksum) as 12
+//! to 24 words from the english wordlist.  `to_seed` stretches it into the
+//! standard 64-byte bip-39 seed, & each curve's `KeyPair::from_mnemonic`
+//! derives a keypair from that seed.  phrases & passphrases are used as
+//! given: bip-39 calls for nfkd normalization, which is a no-op for the
+//! english wordlist & for ascii passphrases, but is left to the caller
+//! otherwise.
+use std::fmt;
+use hmac::{Hmac,Mac};
+use sha2::{Sha256,Sha512,Digest};
+use arith;
+use random;
+use error::Error;
+
+
+type HmacSha512 = Hmac<Sha512>;
+
+
+/// size of a bip-39 seed.
+pub const SEED_SIZE: usize = 64;
+
+/// pbkdf2 iterations used to derive a seed.
+const ROUNDS: u32 = 2048;
+
+/// the english wordlist, one word per line.
+const ENGLISH: &str = include_str!("bip39-english.txt");
+
+
+/// a bip-39 mnemonic.  its `Debug` output is redacted, since anyone who
+/// sees the phrase holds the keys derived from it.
+pub struct Mnemonic {
+    entropy: Vec<u8>,
+}
+
+impl Mnemonic {
+    /// generate a new random mnemonic of `words` words, which must be 12,
+    /// 15, 18, 21 or 24 (giving 128 to 256 bits of entropy); any other
+    /// count yields `Error::MalformedEncoding`.
+    pub fn generate(words: usize) -> Result<Self,Error> {
+        if !words.is_multiple_of(3) {
+            return Err(Error::MalformedEncoding);
+        }
+        let mut entropy = vec![0u8;words / 3 * 4];
+        check_entropy_len(entropy.len())?;
+        random::fill(&mut entropy)?;
+        Ok(Mnemonic { entropy })
+    }
+
+    /// encode `entropy`, which must be 16, 20, 24, 28 or 32 bytes long (or
+    /// `Error::MalformedEncoding` results).
+    pub fn from_entropy(entropy: &[u8]) -> Result<Self,Error> {
+        check_entropy_len(entropy.len())?;
+        Ok(Mnemonic { entropy: entropy.to_vec() })
+    }
+
+    /// parse a phrase of whitespace-separated words.  a phrase of the wrong
+    /// length, with a word not in the wordlist, or whose checksum doesn't
+    /// match yields `Error::MalformedEncoding`.
+    pub fn parse(phrase: &str) -> Result<Self,Error> {
+        let words = wordlist();
+        let mut bits = Vec::with_capacity(24 * 11);
+        for word in phrase.split_whitespace() {
+            let index = words.binary_search(&word).map_err(|_| Error::MalformedEncoding)?;
+            bits.extend((0..11).rev().map(|i| (index >> i) & 1 == 1));
+        }
+        if !bits.len().is_multiple_of(33) {
+            return Err(Error::MalformedEncoding);
+        }
+        let len = bits.len() / 33 * 4;
+        check_entropy_len(len)?;
+        let mut entropy = vec![0u8;len];
+        for (i,bit) in bits[..len * 8].iter().enumerate() {
+            if *bit { entropy[i / 8] |= 0x80 >> (i % 8); }
+        }
+        let mnemonic = Mnemonic { entropy };
+        if bits[len * 8..] != mnemonic.checksum()[..] {
+            return Err(Error::MalformedEncoding);
+        }
+        Ok(mnemonic)
+    }
+
+    /// the encoded entropy.
+    pub fn entropy(&self) -> &[u8] { &self.entropy }
+
+    /// the phrase, as words separated by single spaces.
+    pub fn phrase(&self) -> String {
+        let words = wordlist();
+        let bits: Vec<bool> = self.entropy.iter()
+            .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1))
+            .chain(self.checksum())
+            .collect();
+        let phrase: Vec<&str> = bits.chunks(11)
+            .map(|chunk| words[chunk.iter().fold(0,|index,&bit| index << 1 | bit as usize)])
+            .collect();
+        phrase.join(" ")
+    }
+
+    /// derive the 64-byte bip-39 seed, as pbkdf2-hmac-sha512 over the
+    /// phrase with the salt `"mnemonic" || passphrase` (which may be
+    /// empty).
+    pub fn to_seed(&self, passphrase: &str) -> [u8;SEED_SIZE] {
+        let mut phrase = self.phrase().into_bytes();
+        let salt = ["mnemonic",passphrase].concat();
+        let mac = HmacSha512::new_from_slice(&phrase).expect("hmac accepts keys of any length");
+        // the seed is exactly one pbkdf2 block.
+        let mut u = mac.clone().chain_update(salt.as_bytes()).chain_update([0,0,0,1]).finalize().into_bytes();
+        let mut seed = [0u8;SEED_SIZE];
+        seed.copy_from_slice(&u);
+        for _ in 1..ROUNDS {
+            u = mac.clone().chain_update(&u).finalize().into_bytes();
+            for (out,byte) in seed.iter_mut().zip(u.iter()) {
+                *out ^= byte;
+            }
+        }
+        arith::wipe_bytes(&mut phrase);
+        seed
+    }
+
+    /// the checksum bits: the first `len / 4` bits of the entropy's
+    /// sha-256 hash, for entropy of `len` bytes.
+    fn checksum(&self) -> Vec<bool> {
+        let hash = Sha256::digest(&self.entropy);
+        (0..self.entropy.len() / 4).map(|i| (hash[i / 8] >> (7 - i % 8)) & 1 == 1).collect()
+    }
+}
+
+impl fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("Mnemonic([redacted])") }
+}
+
+impl Drop for Mnemonic {
+    fn drop(&mut self) { arith::wipe_bytes(&mut self.entropy); }
+}
+
+
+/// the english wordlist, in (sorted) index order.
+fn wordlist() -> Vec<&'static str> {
+    ENGLISH.lines().collect()
+}
+
+
+/// check that entropy of `len` bytes can be encoded.
+fn check_entropy_len(len: usize) -> Result<(),Error> {
+    match len {
+        16 | 20 | 24 | 28 | 32 => Ok(()),
+        _ => Err(Error::MalformedEncoding),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use mnemonic::{Mnemonic,wordlist};
+    use error::Error;
+    use hex::unhex;
+
+    // from the reference implementation's test vectors (passphrase
+    // `TREZOR`).
+    const VECTORS: &[(&str,&str,&str)] = &[
+        ("00000000000000000000000000000000",
+         "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+         "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"),
+        ("7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+         "legal winner thank year wave sausage worth useful legal winner thank yellow",
+         "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607"),
+        ("8080808080808080808080808080808080808080808080808080808080808080",
+         "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic bless",
+         "c0c519bd0e91a2ed54357d9d1ebef6f5af218a153624cf4f2da911a0ed8f7a09e2ef61af0aca007096df430022f7a2b6fb91661a9589097069720d015e4e982f"),
+        ("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+         "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+         "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad"),
+    ];
+
+    #[test]
+    fn mnemonic_ok() {
+        assert_eq!(wordlist().len(),2048);
+        for &(entropy, phrase, seed) in VECTORS {
+            let mnemonic = Mnemonic::from_entropy(&unhex(entropy)).unwrap();
+            assert_eq!(mnemonic.phrase(),phrase);
+            assert_eq!(Mnemonic::parse(phrase).unwrap().entropy(),&unhex(entropy)[..]);
+            assert_eq!(mnemonic.to_seed("TREZOR").to_vec(),unhex(seed));
+        }
+        for &words in &[12,15,18,21,24] {
+            let mnemonic = Mnemonic::generate(words).unwrap();
+            let phrase = mnemonic.phrase();
+            assert_eq!(phrase.split(' ').count(),words);
+            assert_eq!(Mnemonic::parse(&phrase).unwrap().entropy(),mnemonic.entropy());
+        }
+        assert_eq!(format!("{:?}",Mnemonic::generate(12).unwrap()),"Mnemonic([redacted])");
+    }
+
+    #[test]
+    fn mnemonic_err() {
+        assert_eq!(Mnemonic::generate(13).err(),Some(Error::MalformedEncoding));
+        assert_eq!(Mnemonic::generate(27).err(),Some(Error::MalformedEncoding));
+        assert_eq!(Mnemonic::from_entropy(&[0u8;17]).err(),Some(Error::MalformedEncoding));
+        // a bad checksum, an unknown word, & a phrase of the wrong length.
+        let bad = [
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abbot",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            "",
+        ];
+        for phrase in &bad {
+            assert_eq!(Mnemonic::parse(phrase).err(),Some(Error::MalformedEncoding));
+        }
+    }
+}
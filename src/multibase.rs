@@ -0,0 +1,84 @@
+//! multibase strings of multicodec-tagged public keys, as used by `did:key`
+//! & multikey in the did ecosystem: a `z` (for `base58btc`), then the
+//! base58 of the key's multicodec code (an unsigned varint) & its bytes.
+//! only built with the `encoding` feature.
+use base58;
+use error::Error;
+
+
+/// the multibase prefix of `base58btc`.
+const BASE58BTC: char = 'z';
+
+/// maximum length of a varint, per the multiformats spec.
+const MAX_VARINT_SIZE: usize = 9;
+
+
+/// encode `key` under the multicodec `code`.
+pub fn encode(code: u64, key: &[u8]) -> String {
+    let mut bytes = Vec::with_capacity(MAX_VARINT_SIZE + key.len());
+    let mut rest = code;
+    while rest >= 0x80 {
+        bytes.push(rest as u8 | 0x80);
+        rest >>= 7;
+    }
+    bytes.push(rest as u8);
+    bytes.extend_from_slice(key);
+    let mut out = String::with_capacity(1 + bytes.len() * 138 / 100 + 1);
+    out.push(BASE58BTC);
+    out.push_str(&base58::encode(&bytes));
+    out
+}
+
+
+/// decode `text`, which must be tagged with the multicodec `code` (or
+/// `Error::InvalidKey` results), returning the key's bytes.  a base other
+/// than `base58btc`, bad base58, or a varint which isn't minimal yields
+/// `Error::MalformedEncoding`.
+pub fn decode(code: u64, text: &str) -> Result<Vec<u8>,Error> {
+    if !text.starts_with(BASE58BTC) { return Err(Error::MalformedEncoding); }
+    let bytes = base58::decode(&text[1..])?;
+    let (mut found, mut len) = (0u64, 0);
+    loop {
+        let byte = *bytes.get(len).ok_or(Error::MalformedEncoding)?;
+        found |= ((byte & 0x7f) as u64) << (7 * len);
+        len += 1;
+        if byte & 0x80 == 0 {
+            // a trailing zero byte would make the varint longer than needed.
+            if byte == 0 && len > 1 { return Err(Error::MalformedEncoding); }
+            break;
+        }
+        if len == MAX_VARINT_SIZE { return Err(Error::MalformedEncoding); }
+    }
+    if found != code { return Err(Error::InvalidKey); }
+    Ok(bytes[len..].to_vec())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use multibase::{encode,decode};
+    use error::Error;
+
+    #[test]
+    fn multibase_ok() {
+        // an ed25519 key (code `0xed`) from the `did:key` spec.
+        let key = [0x2e,0x6f,0xcc,0xe3,0x67,0x01,0xdc,0x79,0x14,0x88,0xe0,0xd0,0xb1,0x74,0x5c,0xc1,0xe3,0x3a,0x4c,0x1c,0x9f,0xcc,0x41,0xc6,0x3b,0xd3,0x43,0xdb,0xbe,0x09,0x70,0xe6];
+        let text = "z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+        assert_eq!(encode(0xed,&key),text);
+        assert_eq!(decode(0xed,text).unwrap(),key);
+        // a two-byte code.
+        assert_eq!(decode(0x1200,&encode(0x1200,&[0xaa;3])).unwrap(),[0xaa;3]);
+    }
+
+    #[test]
+    fn multibase_err() {
+        assert_eq!(decode(0xe7,&encode(0xed,&[0xaa;3])),Err(Error::InvalidKey));
+        // another base, bad base58, & varints which are truncated or
+        // longer than needed.
+        assert_eq!(decode(0xed,"f00"),Err(Error::MalformedEncoding));
+        assert_eq!(decode(0xed,"z0"),Err(Error::MalformedEncoding));
+        assert_eq!(decode(0xed,"z"),Err(Error::MalformedEncoding));
+        assert_eq!(decode(0xed,"z3D"),Err(Error::MalformedEncoding));
+        assert_eq!(decode(0x01,"zApP"),Err(Error::MalformedEncoding));
+    }
+}
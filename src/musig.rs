@@ -0,0 +1,357 @@
+//! multi-signatures over `secp256k1` (musig2, following the key aggregation
+//! & signing algorithms of bip-327, without tweaking), producing ordinary
+//! bip-340 signatures which verify against the aggregate x-only key with
+//! `schnorr::Public::verify`.
+//!
+//! unlike `threshold`, every signer must take part, & no setup is needed
+//! beyond exchanging public keys.  signing takes two rounds: each signer
+//! broadcasts the `PublicNonce` from `commit`, the nonces are summed with
+//! `aggregate_nonces`, & each signer sends its `PartialSignature` to whoever
+//! combines them.  round messages are plain serializable structs.
+//!
+//! a `SecretNonce` holds the two nonces behind a `PublicNonce`, & is
+//! consumed by `Session::sign`.  the other signers choose their own nonces
+//! after seeing this one, so a second session under the same `SecretNonce`
+//! gives them a different aggregate nonce & challenge, & the two partial
+//! signatures reveal the secret key (bip-327 forbids reusing `secnonce` for
+//! this reason).  it can be neither cloned nor serialized, so it can't
+//! survive a restart either: commit afresh after losing one.
+use arith::{self,Curve,Limbs,Point,MAX_LIMBS};
+use secp256k1::{self,PARAMS};
+use schnorr;
+use error::Error;
+use random;
+
+
+/// size of curve.
+const BYTES: usize = 32;
+
+
+/// a set of public keys combined into one aggregate key.  the order of the
+/// keys matters: the same keys in another order aggregate differently.
+pub struct AggregateKey {
+    keys: Vec<secp256k1::Public>,
+    list: [u8;BYTES],
+    q: Point,
+}
+
+impl AggregateKey {
+    /// aggregate the given (non-empty) list of keys.  a key which isn't a
+    /// valid curve point yields `Error::InvalidKey`.
+    pub fn new(keys: &[secp256k1::Public]) -> Result<Self,Error> {
+        if keys.is_empty() {
+            return Err(Error::InvalidParticipants);
+        }
+        let curve = Curve::new(&PARAMS);
+        let mut encoded = Vec::with_capacity(keys.len() * (BYTES + 1));
+        for key in keys {
+            encoded.extend_from_slice(key.as_ref());
+        }
+        let mut key = AggregateKey {
+            keys: keys.to_vec(),
+            list: schnorr::tagged_hash("KeyAgg list",&[&encoded]),
+            q: curve.identity(),
+        };
+        for public in keys {
+            let point = schnorr::decode_point(&curve,public)?;
+            key.q = curve.add(&key.q,&curve.mul(&point,&key.coefficient(&curve,public)));
+        }
+        if curve.to_affine(&key.q).is_none() {
+            return Err(Error::InvalidKey);
+        }
+        Ok(key)
+    }
+
+    /// the aggregated keys, in order.
+    pub fn keys(&self) -> &[secp256k1::Public] { &self.keys }
+
+    /// the aggregate x-only bip-340 public key.
+    pub fn public(&self) -> schnorr::Public {
+        let curve = Curve::new(&PARAMS);
+        let (x,_) = curve.to_affine(&self.q).expect("aggregate key is never infinity");
+        let mut public = [0u8;BYTES];
+        arith::to_be(&x,&mut public);
+        schnorr::Public::from(public)
+    }
+
+    /// the coefficient of `key`: one for the first key differing from the
+    /// first in the list, & a hash of the list & key otherwise.
+    fn coefficient(&self, curve: &Curve, key: &secp256k1::Public) -> Limbs {
+        let second = self.keys.iter().find(|other| *other != &self.keys[0]);
+        if second == Some(key) {
+            let mut one = [0;MAX_LIMBS];
+            one[0] = 1;
+            one
+        } else {
+            curve.fq.reduce(&arith::from_be(&schnorr::tagged_hash("KeyAgg coefficient",&[&self.list,key.as_ref()])))
+        }
+    }
+}
+
+
+/// a signer's secret nonces for one signing session.  they're consumed by
+/// `Session::sign` (& wiped on drop), since signing twice with the same
+/// nonces would leak the secret key.
+pub struct SecretNonce {
+    public: secp256k1::Public,
+    k1: Limbs,
+    k2: Limbs,
+}
+
+impl Drop for SecretNonce {
+    fn drop(&mut self) {
+        arith::wipe(&mut self.k1);
+        arith::wipe(&mut self.k2);
+    }
+}
+
+
+/// a signer's broadcast in the first round of signing (or the sum of every
+/// signer's, from `aggregate_nonces`): the points `k1 G` & `k2 G`.  a sum
+/// which is the point at infinity is encoded as 33 zero bytes.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct PublicNonce {
+    /// the first nonce point.
+    pub r1: secp256k1::Public,
+    /// the second nonce point.
+    pub r2: secp256k1::Public,
+}
+
+
+/// a signer's output in the second round of signing.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct PartialSignature {
+    /// the big-endian response scalar.
+    pub s: [u8;BYTES],
+}
+
+
+/// begin a signing session as the holder of `secret`: draw fresh nonces,
+/// returning them along with the public nonce to broadcast.
+pub fn commit(secret: &secp256k1::Secret) -> Result<(SecretNonce,PublicNonce),Error> {
+    let curve = Curve::new(&PARAMS);
+    let mut nonce = SecretNonce { public: secret.public()?, k1: [0;MAX_LIMBS], k2: [0;MAX_LIMBS] };
+    nonce.k1 = random::scalar(&curve.fq)?;
+    nonce.k2 = random::scalar(&curve.fq)?;
+    let public = PublicNonce {
+        r1: schnorr::encode_point(&curve,&curve.mul(&curve.generator(),&nonce.k1))?,
+        r2: schnorr::encode_point(&curve,&curve.mul(&curve.generator(),&nonce.k2))?,
+    };
+    Ok((nonce,public))
+}
+
+
+/// sum the public nonces of every signer.
+pub fn aggregate_nonces(nonces: &[PublicNonce]) -> Result<PublicNonce,Error> {
+    let curve = Curve::new(&PARAMS);
+    let (mut r1, mut r2) = (curve.identity(), curve.identity());
+    for nonce in nonces {
+        r1 = curve.add(&r1,&schnorr::decode_point(&curve,&nonce.r1)?);
+        r2 = curve.add(&r2,&schnorr::decode_point(&curve,&nonce.r2)?);
+    }
+    Ok(PublicNonce { r1: encode_ext(&curve,&r1), r2: encode_ext(&curve,&r2) })
+}
+
+
+/// the values of a signing session shared by every signer: the aggregate
+/// key, the nonce coefficient `b`, the final nonce `R` & the bip-340
+/// challenge.
+pub struct Session<'a> {
+    key: &'a AggregateKey,
+    msg: &'a [u8],
+    curve: Curve,
+    b: Limbs,
+    rx: [u8;BYTES],
+    odd_r: bool,
+    odd_q: bool,
+    e: Limbs,
+}
+
+impl<'a> Session<'a> {
+    /// set up a session signing `msg` under `key`, with the aggregate of
+    /// every signer's public nonce.
+    pub fn new(key: &'a AggregateKey, aggnonce: &PublicNonce, msg: &'a [u8]) -> Result<Self,Error> {
+        let curve = Curve::new(&PARAMS);
+        let public = key.public();
+        let (qx,qy) = curve.to_affine(&key.q).ok_or(Error::InvalidKey)?;
+        let hash = schnorr::tagged_hash("MuSig/noncecoef",&[aggnonce.r1.as_ref(),aggnonce.r2.as_ref(),public.as_ref(),msg]);
+        let b = curve.fq.reduce(&arith::from_be(&hash));
+        let r = curve.add(&decode_ext(&curve,&aggnonce.r1)?,&curve.mul(&decode_ext(&curve,&aggnonce.r2)?,&b));
+        // a sum at infinity falls back to the generator, as in bip-327.
+        let (rx,ry) = curve.to_affine(&r).or_else(|| curve.to_affine(&curve.generator())).expect("generator is finite");
+        let mut session = Session { key, msg, curve, b, rx: [0u8;BYTES], odd_r: ry[0] & 1 == 1, odd_q: qy[0] & 1 == 1, e: [0;MAX_LIMBS] };
+        arith::to_be(&rx,&mut session.rx);
+        let mut px = [0u8;BYTES];
+        arith::to_be(&qx,&mut px);
+        session.e = schnorr::challenge(&session.curve,&session.rx,&px,msg);
+        Ok(session)
+    }
+
+    /// produce the partial signature of the holder of `secret`, consuming
+    /// the nonces it committed to.  a secret whose public key isn't among
+    /// the aggregated keys yields `Error::InvalidParticipants`, & one which
+    /// doesn't match the nonces yields `Error::InvalidKey`.
+    pub fn sign(&self, nonce: SecretNonce, secret: &secp256k1::Secret) -> Result<PartialSignature,Error> {
+        let (curve, fq) = (&self.curve, &self.curve.fq);
+        let public = secret.public()?;
+        if public != nonce.public {
+            return Err(Error::InvalidKey);
+        }
+        if !self.key.keys.contains(&public) {
+            return Err(Error::InvalidParticipants);
+        }
+        let mut k = fq.add(&nonce.k1,&fq.mul_reduced(&self.b,&nonce.k2));
+        if self.odd_r { k = fq.neg(&k); }
+        let mut d = arith::from_be(secret.as_ref());
+        if self.odd_q { d = fq.neg(&d); }
+        let factor = fq.mul_reduced(&self.e,&self.key.coefficient(curve,&public));
+        let mut ed = fq.mul_reduced(&factor,&d);
+        let s = fq.add(&k,&ed);
+        let mut partial = PartialSignature { s: [0u8;BYTES] };
+        arith::to_be(&s,&mut partial.s);
+        arith::wipe(&mut k);
+        arith::wipe(&mut d);
+        arith::wipe(&mut ed);
+        Ok(partial)
+    }
+
+    /// check the partial signature of the signer with key `public` &
+    /// public nonce `nonce`, so that a bad partial signature can be pinned on
+    /// its signer when `aggregate` fails.  one which doesn't verify yields
+    /// `Error::InvalidSignature`.
+    pub fn verify_partial(&self, partial: &PartialSignature, nonce: &PublicNonce, public: &secp256k1::Public) -> Result<(),Error> {
+        let (curve, fq) = (&self.curve, &self.curve.fq);
+        if !self.key.keys.contains(public) {
+            return Err(Error::InvalidParticipants);
+        }
+        let mut s = arith::from_be(&partial.s);
+        if !fq.contains(&s) {
+            return Err(Error::MalformedSignature);
+        }
+        let r = curve.add(&schnorr::decode_point(curve,&nonce.r1)?,&curve.mul(&schnorr::decode_point(curve,&nonce.r2)?,&self.b));
+        let mut factor = fq.mul_reduced(&self.e,&self.key.coefficient(curve,public));
+        if self.odd_q { factor = fq.neg(&factor); }
+        // with an odd `R` the signer negated its nonces, so check `-sG`.
+        if self.odd_r {
+            s = fq.neg(&s);
+            factor = fq.neg(&factor);
+        }
+        let lhs = curve.mul(&curve.generator(),&s);
+        let rhs = curve.add(&r,&curve.mul(&schnorr::decode_point(curve,public)?,&factor));
+        if curve.equal(&lhs,&rhs) { Ok(()) } else { Err(Error::InvalidSignature) }
+    }
+
+    /// combine every signer's partial signature into a bip-340 signature,
+    /// which is verified against the aggregate key before being returned.
+    /// a signature which doesn't verify yields `Error::InvalidSignature`
+    /// (`verify_partial` identifies the bad partial signatures).
+    pub fn aggregate(&self, partials: &[PartialSignature]) -> Result<schnorr::Signature,Error> {
+        let fq = &self.curve.fq;
+        let mut s = [0;MAX_LIMBS];
+        for partial in partials {
+            let si = arith::from_be(&partial.s);
+            if !fq.contains(&si) {
+                return Err(Error::MalformedSignature);
+            }
+            s = fq.add(&s,&si);
+        }
+        let mut raw = [0u8;BYTES*2];
+        raw[..BYTES].copy_from_slice(&self.rx);
+        arith::to_be(&s,&mut raw[BYTES..]);
+        let sig = schnorr::Signature::from(raw);
+        self.key.public().verify(self.msg,&sig)?;
+        Ok(sig)
+    }
+}
+
+
+/// compress a point, encoding the point at infinity as zeros.
+fn encode_ext(curve: &Curve, point: &Point) -> secp256k1::Public {
+    schnorr::encode_point(curve,point).unwrap_or_else(|_| secp256k1::Public::from([0u8;BYTES+1]))
+}
+
+
+/// decompress a point, decoding zeros as the point at infinity.
+fn decode_ext(curve: &Curve, point: &secp256k1::Public) -> Result<Point,Error> {
+    if point.as_ref().iter().all(|byte| *byte == 0) {
+        Ok(curve.identity())
+    } else {
+        schnorr::decode_point(curve,point)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use musig::{AggregateKey,PartialSignature,Session,commit,aggregate_nonces};
+    use secp256k1::{self,KeyPair};
+    use error::Error;
+    use hex::unhex;
+
+    fn public(pair: &KeyPair) -> secp256k1::Public {
+        *pair.public()
+    }
+
+    #[test]
+    fn key_agg_ok() {
+        // bip-327 key aggregation vectors.
+        let keys: Vec<_> = [
+            "02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "023590A94E768F8E1815C2F24B4D80A8E3149316C3518CE7B7AD338368D038CA66",
+        ].iter().map(|key| unhex(key)).collect();
+        let vectors: [(&[usize],&str);4] = [
+            (&[0,1,2],"90539EEDE565F5D054F32CC0C220126889ED1E5D193BAF15AEF344FE59D4610C"),
+            (&[2,1,0],"6204DE8B083426DC6EAF9502D27024D53FC826BF7D2012148A0575435DF54B2B"),
+            (&[0,0,0],"B436E3BAD62B8CD409969A224731C193D051162D8C5AE8B109306127DA3AA935"),
+            (&[0,0,1,1],"69BC22BFA5D106306E48A20679DE1D7389386124D07571D0D872686028C26A3E"),
+        ];
+        for &(indexes,expected) in vectors.iter() {
+            let list: Vec<_> = indexes.iter().map(|&i| secp256k1::Public::from_slice(&keys[i]).unwrap()).collect();
+            assert_eq!(AggregateKey::new(&list).unwrap().public().as_ref(),&unhex(expected)[..]);
+        }
+    }
+
+    #[test]
+    fn musig_ok() {
+        let pairs: Vec<_> = (0..3).map(|_| KeyPair::generate().unwrap()).collect();
+        let key = AggregateKey::new(&pairs.iter().map(public).collect::<Vec<_>>()).unwrap();
+        let (secnonces, pubnonces): (Vec<_>,Vec<_>) = pairs.iter().map(|pair| commit(pair.secret()).unwrap()).unzip();
+        let aggnonce = aggregate_nonces(&pubnonces).unwrap();
+        let session = Session::new(&key,&aggnonce,b"hello").unwrap();
+        let partials: Vec<_> = pairs.iter().zip(secnonces)
+            .map(|(pair,nonce)| session.sign(nonce,pair.secret()).unwrap())
+            .collect();
+        for ((partial,nonce),pair) in partials.iter().zip(&pubnonces).zip(&pairs) {
+            session.verify_partial(partial,nonce,&public(pair)).unwrap();
+        }
+        let sig = session.aggregate(&partials).unwrap();
+        key.public().verify(b"hello",&sig).unwrap();
+        assert_eq!(key.public().verify(b"hellO",&sig),Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn musig_err() {
+        assert_eq!(AggregateKey::new(&[]).err(),Some(Error::InvalidParticipants));
+        let pairs: Vec<_> = (0..2).map(|_| KeyPair::generate().unwrap()).collect();
+        let key = AggregateKey::new(&pairs.iter().map(public).collect::<Vec<_>>()).unwrap();
+        let (n0, p0) = commit(pairs[0].secret()).unwrap();
+        let (n1, p1) = commit(pairs[1].secret()).unwrap();
+        let pubnonces = [p0,p1];
+        let session_nonce = aggregate_nonces(&pubnonces).unwrap();
+        let session = Session::new(&key,&session_nonce,b"msg").unwrap();
+        // a signer outside the key, & nonces belonging to another signer.
+        let outsider = KeyPair::generate().unwrap();
+        assert_eq!(session.sign(commit(outsider.secret()).unwrap().0,outsider.secret()).err(),Some(Error::InvalidParticipants));
+        assert_eq!(session.sign(n1,pairs[0].secret()).err(),Some(Error::InvalidKey));
+        // a tampered partial signature is caught & attributed.
+        let good = session.sign(n0,pairs[0].secret()).unwrap();
+        session.verify_partial(&good,&pubnonces[0],&public(&pairs[0])).unwrap();
+        let mut bad = PartialSignature { s: good.s };
+        bad.s[31] ^= 0x01;
+        assert_eq!(session.verify_partial(&bad,&pubnonces[0],&public(&pairs[0])),Err(Error::InvalidSignature));
+        assert_eq!(session.aggregate(&[good,bad]).err(),Some(Error::InvalidSignature));
+    }
+}
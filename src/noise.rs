@@ -0,0 +1,116 @@
+//! a diffie-hellman provider for noise protocol implementations, over
+//! `secp256r1` keys.  only built with the `secp256r1` feature.
+//!
+//! noise doesn't itself define a p-256 dh function, so this follows the
+//! conventions of the implementations which do (e.g. `snow`'s `P256`):
+//! public keys are 65-byte sec1 uncompressed points, private keys are
+//! 32-byte big-endian scalars, & `DH()` yields the 32-byte x-coordinate of
+//! the shared point.  an invalid public key is an error (noise permits
+//! either rejecting it or producing a fixed output; rejecting is safer).
+use secp256r1::{self,Public,Secret};
+use error::Error;
+
+
+/// the dh function's name, for protocol names such as
+/// `Noise_XX_P256_AESGCM_SHA256`.
+pub const DH_NAME: &str = "P256";
+
+/// size of a dh output (noise's `DHLEN`).
+pub const DHLEN: usize = secp256r1::SHARED_SECRET_SIZE;
+
+/// size of a serialized public key.
+pub const PUBLIC_KEY_LEN: usize = secp256r1::UNCOMPRESSED_PUBLIC_KEY_SIZE;
+
+/// size of a serialized private key.
+pub const PRIVATE_KEY_LEN: usize = secp256r1::SECRET_KEY_SIZE;
+
+
+/// a noise dh keypair: a secret key & its serialized public key.
+#[derive(Debug,PartialEq,Eq)]
+pub struct KeyPair {
+    secret: Secret,
+    public: [u8;PUBLIC_KEY_LEN],
+}
+
+impl KeyPair {
+    /// generate a new random keypair (noise's `GENERATE_KEYPAIR()`).
+    pub fn generate() -> Result<Self,Error> {
+        let pair = secp256r1::KeyPair::generate()?;
+        KeyPair::from_private(pair.secret().as_ref())
+    }
+
+    /// load a keypair from a serialized private key, which must be a
+    /// scalar in `[1, n-1]` (or `Error::InvalidSecretKey` results).
+    pub fn from_private(private: &[u8]) -> Result<Self,Error> {
+        let secret = Secret::from_slice(private)?;
+        let public = secret.public()?.to_uncompressed()?;
+        Ok(KeyPair { secret, public })
+    }
+
+    /// the serialized public key.
+    pub fn public(&self) -> &[u8;PUBLIC_KEY_LEN] { &self.public }
+
+    /// the serialized private key.
+    pub fn private(&self) -> &[u8] { self.secret.as_ref() }
+
+    /// perform noise's `DH(key_pair, public_key)`, writing `DHLEN` bytes to
+    /// `out`.  a public key of the wrong length, or an `out` which isn't
+    /// `DHLEN` bytes, yields `Error::WrongLength`; one which isn't a valid
+    /// curve point fails as `secp256r1::Public::from_uncompressed` does.
+    pub fn dh(&self, public: &[u8], out: &mut [u8]) -> Result<(),Error> {
+        if out.len() != DHLEN {
+            return Err(Error::WrongLength { expected: DHLEN, got: out.len() });
+        }
+        out.copy_from_slice(secp256r1::ecdh(&parse_public(public)?,&self.secret)?.expose_secret());
+        Ok(())
+    }
+}
+
+
+/// parse a serialized public key.
+fn parse_public(public: &[u8]) -> Result<Public,Error> {
+    if public.len() != PUBLIC_KEY_LEN {
+        return Err(Error::WrongLength { expected: PUBLIC_KEY_LEN, got: public.len() });
+    }
+    let mut point = [0u8;PUBLIC_KEY_LEN];
+    point.copy_from_slice(public);
+    Public::from_uncompressed(point)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use noise::{KeyPair,DHLEN,PUBLIC_KEY_LEN};
+    use error::Error;
+    use hex::unhex;
+
+    #[test]
+    fn dh_ok() {
+        let (a, b) = (KeyPair::generate().unwrap(), KeyPair::generate().unwrap());
+        let (mut ab, mut ba) = ([0u8;DHLEN], [0u8;DHLEN]);
+        a.dh(b.public(),&mut ab).unwrap();
+        b.dh(a.public(),&mut ba).unwrap();
+        assert_eq!(ab,ba);
+        assert_eq!(KeyPair::from_private(a.private()).unwrap(),a);
+        // rfc5903 §8.1.
+        let a = KeyPair::from_private(&unhex("c88f01f510d9ac3f70a292daa2316de544e9aab8afe84049c62a9c57862d1433")).unwrap();
+        let b = KeyPair::from_private(&unhex("c6ef9c5d78ae012a011164acb397ce2088685d8f06bf9be0b283ab46476bee53")).unwrap();
+        assert_eq!(a.public().to_vec(),unhex("04dad0b65394221cf9b051e1feca5787d098dfe637fc90b9ef945d0c37725811805271a0461cdb8252d61f1c456fa3e59ab1f45b33accf5f58389e0577b8990bb3"));
+        assert_eq!(b.public().to_vec(),unhex("04d12dfb5289c8d4f81208b70270398c342296970a0bccb74c736fc7554494bf6356fbf3ca366cc23e8157854c13c58d6aac23f046ada30f8353e74f33039872ab"));
+        a.dh(b.public(),&mut ab).unwrap();
+        assert_eq!(ab.to_vec(),unhex("d6840f6b42f6edafd13116e0e12565202fef8e9ece7dce03812464d04b9442de"));
+    }
+
+    #[test]
+    fn dh_err() {
+        let pair = KeyPair::generate().unwrap();
+        let mut out = [0u8;DHLEN];
+        assert_eq!(pair.dh(&pair.public()[..33],&mut out),Err(Error::WrongLength { expected: PUBLIC_KEY_LEN, got: 33 }));
+        assert_eq!(pair.dh(pair.public(),&mut out[..16]),Err(Error::WrongLength { expected: DHLEN, got: 16 }));
+        let mut off_curve = *pair.public();
+        off_curve[PUBLIC_KEY_LEN - 1] ^= 0x01;
+        assert_eq!(pair.dh(&off_curve,&mut out),Err(Error::InvalidKey));
+        assert_eq!(KeyPair::from_private(&[0u8;32]).err(),Some(Error::InvalidSecretKey));
+        assert_eq!(KeyPair::from_private(&[0x11;31]).err(),Some(Error::WrongLength { expected: 32, got: 31 }));
+    }
+}
@@ -0,0 +1,77 @@
+//! pem armor (rfc7468) around der encodings.  only built with the `pem`
+//! feature.
+use base64;
+use error::Error;
+
+
+/// label of a sec1 `ECPrivateKey`.
+pub const EC_PRIVATE_KEY: &str = "EC PRIVATE KEY";
+
+/// label of a pkcs8 `PrivateKeyInfo`.
+pub const PRIVATE_KEY: &str = "PRIVATE KEY";
+
+/// label of a `SubjectPublicKeyInfo`.
+pub const PUBLIC_KEY: &str = "PUBLIC KEY";
+
+
+/// number of base64 characters per line, as written by openssl.
+const LINE: usize = 64;
+
+
+/// armor `der` with the given label.
+pub fn encode(label: &str, der: &[u8]) -> String {
+    let body = base64::encode(der,base64::STANDARD,true);
+    let mut out = format!("-----BEGIN {}-----\n",label);
+    for line in body.as_bytes().chunks(LINE) {
+        out.push_str(&String::from_utf8_lossy(line));
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {}-----\n",label));
+    out
+}
+
+
+/// strip the armor from `text`, which must consist of exactly one block with
+/// the given label.  blocks with other labels, explanatory headers (such as
+/// the `Proc-Type` of an encrypted key), or stray text yield
+/// `Error::MalformedEncoding`.
+pub fn decode(label: &str, text: &str) -> Result<Vec<u8>,Error> {
+    let begin = format!("-----BEGIN {}-----",label);
+    let end = format!("-----END {}-----",label);
+    let mut lines = text.trim().lines().map(|line| line.trim_end_matches('\r'));
+    if lines.next() != Some(&begin[..]) {
+        return Err(Error::MalformedEncoding);
+    }
+    let mut body = String::new();
+    let mut closed = false;
+    for line in lines {
+        if closed || line.len() > LINE {
+            return Err(Error::MalformedEncoding);
+        }
+        if line == end {
+            closed = true;
+        } else {
+            body.push_str(line);
+        }
+    }
+    if !closed { return Err(Error::MalformedEncoding); }
+    base64::decode(&body,base64::STANDARD,true)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use pem;
+    use error::Error;
+
+    #[test]
+    fn armor_err() {
+        let text = pem::encode("EC PRIVATE KEY",&[1,2,3]);
+        assert_eq!(pem::decode("EC PRIVATE KEY",&text).unwrap(),vec![1,2,3]);
+        assert_eq!(pem::decode("PRIVATE KEY",&text),Err(Error::MalformedEncoding));
+        assert_eq!(pem::decode("EC PRIVATE KEY",&text.replace("-----END","-----FIN")),Err(Error::MalformedEncoding));
+        assert_eq!(pem::decode("EC PRIVATE KEY",&format!("{}trailing\n",text)),Err(Error::MalformedEncoding));
+        let encrypted = text.replace("\nAQID","\nProc-Type: 4,ENCRYPTED\n\nAQID");
+        assert_eq!(pem::decode("EC PRIVATE KEY",&encrypted),Err(Error::MalformedEncoding));
+    }
+}
@@ -0,0 +1,84 @@
+//! portable backend, implementing the operations of a curve module on top of
+//! `arith` for curves which `easy-ecc` doesn't support.  keys, hashes &
+//! signatures are passed in the same byte layouts `easy-ecc` uses.
+use arith::{self,Curve,Limbs,Params};
+use error::Error;
+use ecdsa;
+use random;
+
+
+/// generate a new keypair.
+pub fn keygen(params: &Params, public: &mut [u8], secret: &mut [u8]) -> Result<(),Error> {
+    let curve = Curve::new(params);
+    let mut d = random::scalar(&curve.fq)?;
+    curve.compress(&curve.mul(&curve.generator(),&d),public);
+    arith::to_be(&d,secret);
+    arith::wipe(&mut d);
+    Ok(())
+}
+
+
+/// sign `hash`, writing `r || s` into `sig`.
+pub fn sign(params: &Params, secret: &[u8], hash: &[u8], sig: &mut [u8]) -> Result<(),Error> {
+    sign_recoverable(params,secret,hash,sig).map(|_| ())
+}
+
+
+/// sign `hash` as `sign` does, returning the recovery id of the signature.
+pub fn sign_recoverable(params: &Params, secret: &[u8], hash: &[u8], sig: &mut [u8]) -> Result<u8,Error> {
+    let curve = Curve::new(params);
+    let mut d = arith::from_be(secret);
+    let rslt = if curve.is_scalar(&d) {
+        sign_random(&curve,&d,hash,sig)
+    } else {
+        Err(Error::InvalidSecretKey)
+    };
+    arith::wipe(&mut d);
+    rslt
+}
+
+
+/// sign with fresh random nonces until a non-degenerate signature results,
+/// giving up with `Error::RetriesExhausted` after `random::MAX_TRIES` nonces.
+fn sign_random(curve: &Curve, d: &Limbs, hash: &[u8], sig: &mut [u8]) -> Result<u8,Error> {
+    for _ in 0..random::MAX_TRIES {
+        let mut k = random::scalar(&curve.fq)?;
+        let id = ecdsa::sign_with_nonce_recoverable(curve,d,hash,&k,sig);
+        arith::wipe(&mut k);
+        if let Some(id) = id { return Ok(id); }
+    }
+    Err(Error::RetriesExhausted)
+}
+
+
+/// verify a signature `r || s` over `hash`.
+#[cfg_attr(not(any(feature = "brainpoolp256r1", feature = "secp256k1", feature = "secp521r1")), allow(dead_code))]
+pub fn verify(params: &Params, public: &[u8], hash: &[u8], sig: &[u8]) -> Result<(),Error> {
+    let curve = Curve::new(params);
+    let point = curve.decompress(public).ok_or(Error::InvalidKey)?;
+    let len = sig.len() / 2;
+    let r = arith::from_be(&sig[..len]);
+    let s = arith::from_be(&sig[len..]);
+    if !curve.is_scalar(&r) || !curve.is_scalar(&s) {
+        return Err(Error::MalformedSignature);
+    }
+    if ecdsa::verify(&curve,&point,hash,&r,&s) { Ok(()) } else { Err(Error::InvalidSignature) }
+}
+
+
+/// derive the ecdh shared secret (the x-coordinate of the shared point).
+pub fn ecdh(params: &Params, public: &[u8], secret: &[u8], shared: &mut [u8]) -> Result<(),Error> {
+    let curve = Curve::new(params);
+    let point = curve.decompress(public).ok_or(Error::InvalidKey)?;
+    let mut d = arith::from_be(secret);
+    let rslt = if curve.is_scalar(&d) {
+        match curve.to_affine(&curve.mul(&point,&d)) {
+            Some((x,_)) => { arith::to_be(&x,shared); Ok(()) },
+            None => Err(Error::InvalidKey),
+        }
+    } else {
+        Err(Error::InvalidSecretKey)
+    };
+    arith::wipe(&mut d);
+    rslt
+}
@@ -0,0 +1,268 @@
+//! operating system randomness, & uniformly random scalars.  all of the
+//! crate's randomness (including nonces for the `easy-ecc` curves, whose own
+//! rng is bypassed) is drawn through `fill`.
+//!
+//! rather than asking the os for every draw, each thread keeps an hmac-drbg
+//! seeded from the os, which it reseeds after `RESEED_INTERVAL` requests &
+//! after the process forks (so that parent & child never share output).
+//! with the `custom-rng` feature, `set_entropy_source` can replace the os as
+//! the source of seed material.
+use std::cell::RefCell;
+use std::process;
+#[cfg(feature = "custom-rng")]
+use std::sync::RwLock;
+#[cfg(feature = "custom-rng")]
+use std::sync::atomic::{AtomicUsize,Ordering};
+use getrandom;
+use arith::{self,Limbs,Modulus,MAX_LIMBS};
+use drbg::{self,Drbg};
+use error::Error;
+
+
+/// number of attempts made at a randomized operation, such as drawing a
+/// usable scalar.
+pub const MAX_TRIES: usize = 16;
+
+
+/// bytes of os randomness seeding a thread's drbg: a 256-bit entropy input
+/// followed by a 128-bit nonce.
+const SEED_LEN: usize = 48;
+
+
+/// requests a thread's drbg serves before it's reseeded from the os.
+const RESEED_INTERVAL: u64 = 1 << 16;
+
+
+/// a source of entropy, which fills its argument with random bytes.
+#[cfg(feature = "custom-rng")]
+pub type EntropySource = fn(&mut [u8]) -> Result<(),Error>;
+
+
+/// the registered entropy source, if any.
+#[cfg(feature = "custom-rng")]
+static SOURCE: RwLock<Option<EntropySource>> = RwLock::new(None);
+
+
+/// number of times an entropy source has been registered, so that threads
+/// can tell when to reseed.
+#[cfg(feature = "custom-rng")]
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+
+/// seed all randomness (for every thread, from the next draw onwards) from
+/// `source` in place of the os, for targets such as microcontrollers which
+/// have a hardware rng but no os to ask.  an error returned by `source` is
+/// passed on to the operation which needed randomness, so a source should
+/// report its failures as `Error::RandomnessUnavailable`.  only built with
+/// the `custom-rng` feature.
+#[cfg(feature = "custom-rng")]
+pub fn set_entropy_source(source: EntropySource) {
+    *SOURCE.write().unwrap_or_else(|err| err.into_inner()) = Some(source);
+    GENERATION.fetch_add(1,Ordering::SeqCst);
+}
+
+
+/// the current entropy source registration.
+#[cfg(feature = "custom-rng")]
+fn generation() -> usize { GENERATION.load(Ordering::SeqCst) }
+
+#[cfg(not(feature = "custom-rng"))]
+fn generation() -> usize { 0 }
+
+
+/// a thread's drbg, along with the process it was seeded in & the entropy
+/// source registration it was seeded under.
+struct State {
+    drbg: Drbg,
+    pid: u32,
+    generation: usize,
+}
+
+
+thread_local! {
+    static STATE: RefCell<Option<State>> = const { RefCell::new(None) };
+}
+
+
+/// fill `buf` with random bytes from this thread's drbg, (re)seeding it from
+/// the os first if need be.  a failure to get seed material yields
+/// `Error::RandomnessUnavailable`.
+pub fn fill(buf: &mut [u8]) -> Result<(),Error> {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let (pid, generation) = (process::id(), generation());
+        let stale = match *state {
+            Some(ref current) => current.pid != pid || current.generation != generation || current.drbg.requests() >= RESEED_INTERVAL,
+            None => true,
+        };
+        if stale {
+            let mut seed = [0u8;SEED_LEN];
+            let rslt = seed_fill(&mut seed);
+            if rslt.is_ok() { *state = Some(State { drbg: Drbg::new(&seed), pid, generation }); }
+            arith::wipe_bytes(&mut seed);
+            rslt?;
+        }
+        let drbg = &mut state.as_mut().expect("seeded above").drbg;
+        for chunk in buf.chunks_mut(drbg::MAX_REQUEST) {
+            drbg.generate(chunk);
+        }
+        Ok(())
+    })
+}
+
+
+/// fill `buf` with seed material from the registered entropy source, if
+/// there is one, or else from the os.
+#[cfg(feature = "custom-rng")]
+fn seed_fill(buf: &mut [u8]) -> Result<(),Error> {
+    let source = *SOURCE.read().unwrap_or_else(|err| err.into_inner());
+    match source {
+        Some(source) => source(buf),
+        None => os_fill(buf),
+    }
+}
+
+#[cfg(not(feature = "custom-rng"))]
+fn seed_fill(buf: &mut [u8]) -> Result<(),Error> { os_fill(buf) }
+
+
+/// fill `buf` with random bytes from the operating system (via `getrandom`,
+/// which uses a syscall where one exists rather than opening a device, so
+/// works in chroots & without free file descriptors).  a failure yields
+/// `Error::RandomnessUnavailable`.
+fn os_fill(buf: &mut [u8]) -> Result<(),Error> {
+    getrandom::getrandom(buf).map_err(|_| Error::RandomnessUnavailable)
+}
+
+
+/// size of the blocks which `rng_selftest` checks for constant output.
+const SELFTEST_BLOCK: usize = 16;
+
+
+/// check the randomness path, for services to run at startup (or
+/// periodically): the drbg must reproduce its nist known-answer test, & two
+/// draws each from the entropy source & from the drbg must differ, with no
+/// 16-byte block of either consisting of a single repeated byte (as a stuck
+/// source, or one which fills only part of its buffer, would give).  a
+/// source which fails outright yields `Error::RandomnessUnavailable`, &
+/// output which fails the checks yields `Error::RngFailure`.
+pub fn rng_selftest() -> Result<(),Error> {
+    if !drbg::self_test() {
+        return Err(Error::RngFailure);
+    }
+    let mut draws = [[0u8;SEED_LEN];4];
+    let mut rslt = seed_fill(&mut draws[0])
+        .and_then(|()| seed_fill(&mut draws[1]))
+        .and_then(|()| fill(&mut draws[2]))
+        .and_then(|()| fill(&mut draws[3]));
+    if rslt.is_ok() && !(plausible(&draws[0],&draws[1]) && plausible(&draws[2],&draws[3])) {
+        rslt = Err(Error::RngFailure);
+    }
+    for draw in draws.iter_mut() {
+        arith::wipe_bytes(draw);
+    }
+    rslt
+}
+
+
+/// check that two draws differ & that neither has a constant block.
+fn plausible(a: &[u8], b: &[u8]) -> bool {
+    let constant = |block: &[u8]| block.iter().all(|&byte| byte == block[0]);
+    a != b && !a.chunks(SELFTEST_BLOCK).chain(b.chunks(SELFTEST_BLOCK)).any(constant)
+}
+
+
+/// draw a uniformly random scalar in the range `[1,n)` by rejection sampling,
+/// failing with `Error::RngFailure` if every draw is out of range.
+pub fn scalar(fq: &Modulus) -> Result<Limbs,Error> {
+    scalar_with(fq,fill)
+}
+
+
+/// `scalar`, drawing randomness from `fill` in place of the os.
+pub fn scalar_with<F: FnMut(&mut [u8]) -> Result<(),Error>>(fq: &Modulus, mut fill: F) -> Result<Limbs,Error> {
+    let bits = fq.bits();
+    let len = bits.div_ceil(8);
+    let mut buf = [0u8;MAX_LIMBS*8];
+    for _ in 0..MAX_TRIES {
+        fill(&mut buf[..len])?;
+        let mut k = arith::bits2int(&buf[..len],bits);
+        arith::wipe_bytes(&mut buf);
+        if !arith::is_zero(&k) && fq.contains(&k) { return Ok(k); }
+        arith::wipe(&mut k);
+    }
+    Err(Error::RngFailure)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use random::{fill,generation,plausible,rng_selftest,State,STATE,RESEED_INTERVAL};
+    use drbg::Drbg;
+    use std::process;
+
+    #[test]
+    fn fill_ok() {
+        let (mut a, mut b) = ([0u8;32], [0u8;32]);
+        fill(&mut a).unwrap();
+        fill(&mut b).unwrap();
+        assert!(a != [0u8;32] && a != b);
+        fill(&mut []).unwrap();
+    }
+
+    #[test]
+    fn selftest_ok() {
+        rng_selftest().unwrap();
+        let good = (0..32).collect::<Vec<u8>>();
+        let other = (1..33).collect::<Vec<u8>>();
+        assert!(plausible(&good,&other));
+        assert!(!plausible(&good,&good));
+        // a draw which was only half filled.
+        let mut short = good.clone();
+        for byte in short[16..].iter_mut() { *byte = 0; }
+        assert!(!plausible(&short,&other));
+    }
+
+    #[test]
+    fn fill_reseed() {
+        // a drbg which has served its quota, or was seeded in another
+        // process, is replaced by a freshly seeded one.
+        let mut known = [0u8;32];
+        Drbg::new(&[0u8;48]).generate(&mut known);
+        for &(requests, pid) in &[(RESEED_INTERVAL,process::id()),(0,process::id().wrapping_add(1))] {
+            STATE.with(|state| {
+                let mut drbg = Drbg::new(&[0u8;48]);
+                while drbg.requests() < requests { drbg.generate(&mut []); }
+                *state.borrow_mut() = Some(State { drbg, pid, generation: generation() });
+            });
+            let mut out = [0u8;32];
+            fill(&mut out).unwrap();
+            assert!(out != known);
+            STATE.with(|state| assert_eq!(state.borrow().as_ref().unwrap().drbg.requests(),1));
+        }
+    }
+
+    #[cfg(feature = "custom-rng")]
+    #[test]
+    fn entropy_source_ok() {
+        use random::{os_fill,set_entropy_source};
+        use std::cell::Cell;
+        use error::Error;
+        // a source which passes the os's randomness through (so as not to
+        // disturb tests running alongside), counting its calls per thread.
+        thread_local! {
+            static CALLS: Cell<usize> = const { Cell::new(0) };
+        }
+        fn source(buf: &mut [u8]) -> Result<(),Error> {
+            CALLS.with(|calls| calls.set(calls.get() + 1));
+            os_fill(buf)
+        }
+        let mut out = [0u8;32];
+        fill(&mut out).unwrap();
+        set_entropy_source(source);
+        // this thread's drbg reseeds from the new source, once.
+        fill(&mut out).unwrap();
+        fill(&mut out).unwrap();
+        assert_eq!(CALLS.with(|calls| calls.get()),1);
+    }
+}
@@ -0,0 +1,94 @@
+//! deterministic nonce generation as described in rfc6979 §3.2, using
+//! hmac-sha256, along with the §3.6 variant which mixes in additional data
+//! (such as fresh randomness, for hedged signatures).
+use hmac::{Hmac,Mac};
+use sha2::Sha256;
+use arith::{self,Limbs,Modulus,MAX_LIMBS};
+
+
+type HmacSha256 = Hmac<Sha256>;
+
+
+/// size of an hmac-sha256 output.
+const HLEN: usize = 32;
+
+
+/// endless generator of candidate nonces for a given secret & message hash.
+pub struct Nonces<'a> {
+    fq: &'a Modulus,
+    k: [u8;HLEN],
+    v: [u8;HLEN],
+}
+
+
+impl<'a> Nonces<'a> {
+
+    /// seed the generator with secret scalar `x` & message hash `hash`.
+    pub fn new(fq: &'a Modulus, x: &Limbs, hash: &[u8]) -> Self {
+        Nonces::with_extra(fq,x,hash,&[])
+    }
+
+    /// seed the generator as `new` does, appending the additional data
+    /// `extra` (`k'` in §3.6) to the seed.  an empty `extra` gives the same
+    /// nonces as `new`.
+    pub fn with_extra(fq: &'a Modulus, x: &Limbs, hash: &[u8], extra: &[u8]) -> Self {
+        let rlen = fq.bits().div_ceil(8);
+        let mut nonces = Nonces { fq, k: [0x00;HLEN], v: [0x01;HLEN] };
+        // `int2octets(x)` & `bits2octets(hash)`.
+        let mut xo = [0u8;MAX_LIMBS*8];
+        let mut ho = [0u8;MAX_LIMBS*8];
+        arith::to_be(x,&mut xo[..rlen]);
+        arith::to_be(&fq.reduce(&arith::bits2int(hash,fq.bits())),&mut ho[..rlen]);
+        for tag in &[0x00u8,0x01] {
+            nonces.k = nonces.hmac(&[&nonces.v,&[*tag],&xo[..rlen],&ho[..rlen],extra]);
+            nonces.v = nonces.hmac(&[&nonces.v]);
+        }
+        arith::wipe_bytes(&mut xo);
+        nonces
+    }
+
+    /// compute `HMAC_K(parts[0] || parts[1] || ...)`.
+    fn hmac(&self, parts: &[&[u8]]) -> [u8;HLEN] {
+        let mut mac = HmacSha256::new_from_slice(&self.k).expect("hmac accepts keys of any length");
+        for part in parts {
+            mac.update(part);
+        }
+        let mut out = [0u8;HLEN];
+        out.copy_from_slice(&mac.finalize().into_bytes());
+        out
+    }
+}
+
+
+impl<'a> Iterator for Nonces<'a> {
+    type Item = Limbs;
+
+    /// produce the next candidate nonce (always in the range `[1,q)`).
+    fn next(&mut self) -> Option<Limbs> {
+        let rlen = self.fq.bits().div_ceil(8);
+        loop {
+            let mut t = [0u8;MAX_LIMBS*8+HLEN];
+            let mut tlen = 0;
+            while tlen < rlen {
+                self.v = self.hmac(&[&self.v]);
+                t[tlen..tlen+HLEN].copy_from_slice(&self.v);
+                tlen += HLEN;
+            }
+            let k = arith::bits2int(&t[..rlen],self.fq.bits());
+            arith::wipe_bytes(&mut t);
+            // step h.3, which also prepares the state for any retry.
+            self.k = self.hmac(&[&self.v,&[0x00]]);
+            self.v = self.hmac(&[&self.v]);
+            if !arith::is_zero(&k) && self.fq.contains(&k) { return Some(k); }
+        }
+    }
+}
+
+
+#[cfg(feature = "zeroize")]
+impl<'a> Drop for Nonces<'a> {
+    fn drop(&mut self) {
+        arith::wipe_bytes(&mut self.k);
+        arith::wipe_bytes(&mut self.v);
+    }
+}
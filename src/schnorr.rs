@@ -0,0 +1,387 @@
+//! types and functions for schnorr signatures over `secp256k1`, as described
+//! in bip-340.
+//!
+//! like the `secp256k1` module, this one is backed by the crate's portable
+//! arithmetic.  unlike ecdsa, public keys are x-only (the 32-byte
+//! x-coordinate of a point with even y), messages are signed whole, & every
+//! hash is domain-separated by a bip-340 tagged hash.
+use arith::{self,Curve,Limbs,Point,MAX_LIMBS};
+use sha2::{Sha256,Digest};
+use secp256k1::{self,PARAMS};
+use error::Error;
+use random;
+
+
+/// size of curve.
+const BYTES: usize = 32;
+
+
+/// size of a serialized (x-only) public key.
+pub const PUBLIC_KEY_SIZE: usize = BYTES;
+
+/// size of a serialized secret key.
+pub const SECRET_KEY_SIZE: usize = BYTES;
+
+/// size of a serialized signature.
+pub const SIGNATURE_SIZE: usize = BYTES * 2;
+
+/// size of the auxiliary randomness mixed into each nonce.
+pub const AUX_SIZE: usize = BYTES;
+
+
+/// a public bip-340 key (the x-coordinate of a point with even y).
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,PartialOrd,Ord)]
+pub struct Public([u8;BYTES]);
+impl_newtype_bytearray!(Public,BYTES);
+impl_serde_bytearray!(Public,BYTES);
+impl_hex_fmt!(Public);
+impl_from_slice!(Public,PUBLIC_KEY_SIZE);
+impl_bytes!(Public,PUBLIC_KEY_SIZE);
+
+impl Default for Public {
+    fn default() -> Self { Public([0u8;BYTES]) }
+}
+
+impl Public {
+    /// verify a signature over `msg` against this public key.
+    ///
+    /// a signature which simply doesn't match yields
+    /// `Error::InvalidSignature`, while a key which isn't the x-coordinate
+    /// of a curve point yields `Error::InvalidKey` & a signature whose `r`
+    /// or `s` is out of range yields `Error::MalformedSignature`.
+    pub fn verify(&self, msg: &[u8], sig: &Signature) -> Result<(),Error> {
+        let curve = Curve::new(&PARAMS);
+        let point = curve.lift_x(&arith::from_be(&self.0),false).ok_or(Error::InvalidKey)?;
+        let r = arith::from_be(&sig.0[..BYTES]);
+        let s = arith::from_be(&sig.0[BYTES..]);
+        if !curve.fp.contains(&r) || !curve.fq.contains(&s) {
+            return Err(Error::MalformedSignature);
+        }
+        let e = challenge(&curve,&sig.0[..BYTES],&self.0,msg);
+        // `R = sG - eP`.
+        let minus_e = curve.fq.sub(&[0;MAX_LIMBS],&e);
+        let point = curve.add(&curve.mul(&curve.generator(),&s),&curve.mul(&point,&minus_e));
+        match curve.to_affine(&point) {
+            Some((x,y)) if y[0] & 1 == 0 && x == r => Ok(()),
+            _ => Err(Error::InvalidSignature),
+        }
+    }
+}
+
+
+impl From<secp256k1::XOnlyPublic> for Public {
+    fn from(x: secp256k1::XOnlyPublic) -> Self {
+        let mut public = Public::default();
+        public.0.copy_from_slice(x.as_ref());
+        public
+    }
+}
+
+impl From<Public> for secp256k1::XOnlyPublic {
+    fn from(public: Public) -> Self { secp256k1::XOnlyPublic::from(public.0) }
+}
+
+/// a secret bip-340 key (a big-endian scalar in the range `[1,n)`).
+pub struct Secret([u8;BYTES]);
+impl_newtype_bytearray!(Secret,BYTES);
+impl_redacted_fmt!(Secret);
+impl_ct_eq!(Secret);
+impl_serde_bytearray!(Secret,BYTES);
+impl_from_slice!(Secret,SECRET_KEY_SIZE);
+
+impl Default for Secret {
+    fn default() -> Self { Secret([0u8;BYTES]) }
+}
+
+impl Secret {
+    /// generate a new random secret key.
+    pub fn generate() -> Result<Self,Error> {
+        let curve = Curve::new(&PARAMS);
+        let mut d = random::scalar(&curve.fq)?;
+        let mut secret = Secret::default();
+        arith::to_be(&d,&mut secret.0);
+        arith::wipe(&mut d);
+        Ok(secret)
+    }
+
+    /// the raw secret key bytes, for the rare cases (such as export) where
+    /// they must be shown.  never log them.
+    pub fn expose_secret(&self) -> &[u8;BYTES] { &self.0 }
+
+    /// derive the x-only public key corresponding to this secret key.
+    /// yields `Error::InvalidSecretKey` if the scalar is out of range.
+    pub fn public(&self) -> Result<Public,Error> {
+        let curve = Curve::new(&PARAMS);
+        let mut d = self.scalar(&curve)?;
+        let (x,_) = curve.to_affine(&curve.mul(&curve.generator(),&d)).ok_or(Error::InvalidSecretKey)?;
+        arith::wipe(&mut d);
+        let mut public = Public::default();
+        arith::to_be(&x,&mut public.0);
+        Ok(public)
+    }
+
+    /// generate a signature over `msg` with this secret key, drawing fresh
+    /// auxiliary randomness from the operating system.
+    pub fn sign(&self, msg: &[u8]) -> Result<Signature,Error> {
+        let mut aux = [0u8;AUX_SIZE];
+        random::fill(&mut aux)?;
+        self.sign_with_aux(msg,&aux)
+    }
+
+    /// generate a signature over `msg` with this secret key & the supplied
+    /// auxiliary randomness (bip-340 recommends fresh random bytes, but an
+    /// all-zero `aux` still yields a secure, deterministic signature).  the
+    /// signature is verified before being returned, so that a computational
+    /// fault yields `Error::FaultDetected` rather than a bad signature.
+    pub fn sign_with_aux(&self, msg: &[u8], aux: &[u8;AUX_SIZE]) -> Result<Signature,Error> {
+        let curve = Curve::new(&PARAMS);
+        let fq = &curve.fq;
+        let mut d = self.scalar(&curve)?;
+        let (px,py) = curve.to_affine(&curve.mul(&curve.generator(),&d)).ok_or(Error::InvalidSecretKey)?;
+        if py[0] & 1 == 1 { d = fq.sub(&[0;MAX_LIMBS],&d); }
+        let mut public = Public::default();
+        arith::to_be(&px,&mut public.0);
+        // `t = bytes(d) xor hash_aux(aux)`.
+        let mut t = [0u8;BYTES];
+        arith::to_be(&d,&mut t);
+        for (byte,mask) in t.iter_mut().zip(tagged_hash("BIP0340/aux",&[aux]).iter()) {
+            *byte ^= mask;
+        }
+        let mut k = fq.reduce(&arith::from_be(&tagged_hash("BIP0340/nonce",&[&t,&public.0,msg])));
+        arith::wipe_bytes(&mut t);
+        if arith::is_zero(&k) {
+            arith::wipe(&mut d);
+            return Err(Error::RetriesExhausted);
+        }
+        let (rx,ry) = curve.to_affine(&curve.mul(&curve.generator(),&k)).ok_or(Error::RetriesExhausted)?;
+        if ry[0] & 1 == 1 { k = fq.sub(&[0;MAX_LIMBS],&k); }
+        let mut sig = Signature::default();
+        arith::to_be(&rx,&mut sig.0[..BYTES]);
+        let e = challenge(&curve,&sig.0[..BYTES],&public.0,msg);
+        let mut ed = fq.canonical(&fq.mul(&fq.mont_form(&e),&fq.mont_form(&d)));
+        let s = fq.add(&k,&ed);
+        arith::to_be(&s,&mut sig.0[BYTES..]);
+        arith::wipe(&mut d);
+        arith::wipe(&mut k);
+        arith::wipe(&mut ed);
+        public.verify(msg,&sig).map_err(|_| Error::FaultDetected)?;
+        Ok(sig)
+    }
+
+    /// load the secret scalar, checking that it lies in the range `[1,n)`.
+    fn scalar(&self, curve: &Curve) -> Result<Limbs,Error> {
+        let d = arith::from_be(&self.0);
+        if curve.is_scalar(&d) { Ok(d) } else { Err(Error::InvalidSecretKey) }
+    }
+}
+
+impl_zeroize!(Secret);
+
+
+/// a bip-340 signature (`bytes(R.x) || bytes(s)`).
+#[derive(Clone,Copy)]
+pub struct Signature([u8;BYTES*2]);
+impl_newtype_bytearray_ext!(Signature,BYTES*2);
+impl_serde_bytearray!(Signature,BYTES*2);
+impl_hex_fmt!(Signature);
+impl_from_slice!(Signature,SIGNATURE_SIZE);
+impl_bytes!(Signature,SIGNATURE_SIZE);
+
+
+/// a public/secret bip-340 keypair.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct KeyPair {
+    public: Public,
+    secret: Secret,
+}
+
+impl KeyPair {
+    /// generate a new random keypair.
+    pub fn generate() -> Result<Self,Error> {
+        let secret = Secret::generate()?;
+        Ok(KeyPair { public: secret.public()?, secret })
+    }
+
+    /// get the public half of this keypair.
+    pub fn public(&self) -> &Public { &self.public }
+
+    /// get the secret half of this keypair.
+    pub fn secret(&self) -> &Secret { &self.secret }
+
+    /// generate a signature over `msg` with the secret key.
+    pub fn sign(&self, msg: &[u8]) -> Result<Signature,Error> {
+        self.secret.sign(msg)
+    }
+}
+
+
+/// generate a new bip-340 keypair into `public` & `secret`, which are left
+/// untouched on failure.
+#[deprecated(note = "use `KeyPair::generate` or `Secret::generate`, which return the new keys")]
+pub fn keygen(public: &mut Public, secret: &mut Secret) -> Result<(),Error> {
+    let pair = KeyPair::generate()?;
+    *public = pair.public;
+    *secret = pair.secret;
+    Ok(())
+}
+
+
+/// generate a new bip-340 signature.
+pub fn sign(key: &Secret, msg: &[u8], sig: &mut Signature) -> Result<(),Error> {
+    *sig = key.sign(msg)?;
+    Ok(())
+}
+
+
+/// verify a bip-340 signature.
+pub fn verify(key: &Public, msg: &[u8], sig: &Signature) -> Result<(),Error> {
+    key.verify(msg,sig)
+}
+
+
+/// the tagged hash `sha256(sha256(tag) || sha256(tag) || parts...)`.
+pub(crate) fn tagged_hash(tag: &str, parts: &[&[u8]]) -> [u8;BYTES] {
+    let prefix = Sha256::digest(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(&prefix[..]);
+    hasher.update(&prefix[..]);
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut out = [0u8;BYTES];
+    out.copy_from_slice(&hasher.finalize());
+    out
+}
+
+
+/// the challenge scalar `hash_challenge(R.x || P.x || msg) mod n`.
+pub(crate) fn challenge(curve: &Curve, rx: &[u8], px: &[u8], msg: &[u8]) -> Limbs {
+    curve.fq.reduce(&arith::from_be(&tagged_hash("BIP0340/challenge",&[rx,px,msg])))
+}
+
+
+/// compress a point into a `secp256k1` public key, for the multi-party
+/// protocols built on this module.  the point at infinity yields
+/// `Error::InvalidKey`.
+pub(crate) fn encode_point(curve: &Curve, point: &Point) -> Result<secp256k1::Public,Error> {
+    let mut bytes = [0u8;BYTES+1];
+    if curve.compress(point,&mut bytes) { Ok(secp256k1::Public::from(bytes)) } else { Err(Error::InvalidKey) }
+}
+
+
+/// decompress a `secp256k1` public key, yielding `Error::InvalidKey` if it
+/// isn't a valid curve point.
+pub(crate) fn decode_point(curve: &Curve, point: &secp256k1::Public) -> Result<Point,Error> {
+    curve.decompress(point.as_ref()).ok_or(Error::InvalidKey)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use schnorr::{BYTES,Public,Secret,Signature,KeyPair,sign,verify};
+    use error::Error;
+    use hex::unhex;
+
+    #[test]
+    #[allow(deprecated)]
+    fn keygen_ok() {
+        use schnorr::keygen;
+        let mut public = Public::default();
+        let mut secret = Secret::default();
+        keygen(&mut public, &mut secret).unwrap();
+
+        assert!(secret != Secret::default());
+        assert_eq!(secret.public().unwrap(),public);
+    }
+
+    #[test]
+    fn x_only_ok() {
+        use secp256k1;
+        // a bip-340 key is the x-only form of the ecdsa key with the same
+        // secret, whichever the parity of its y-coordinate.
+        for _ in 0..4 {
+            let secret = secp256k1::Secret::generate().unwrap();
+            let (x, _) = secret.public().unwrap().to_x_only().unwrap();
+            let mut bytes = [0u8;BYTES];
+            bytes.copy_from_slice(secret.as_ref());
+            let public = Secret::from(bytes).public().unwrap();
+            assert_eq!(Public::from(secp256k1::XOnlyPublic::from_slice(x.as_ref()).unwrap()),public);
+            assert_eq!(secp256k1::XOnlyPublic::from(public),x);
+        }
+    }
+
+    #[test]
+    fn signing_ok() {
+        let pair = KeyPair::generate().unwrap();
+        let mut sig = Signature::default();
+        sign(pair.secret(),b"hello",&mut sig).unwrap();
+        verify(pair.public(),b"hello",&sig).unwrap();
+        assert_eq!(verify(pair.public(),b"hellO",&sig),Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn bip340_ok() {
+        // bip-340 test vectors 0-3.
+        let vectors = [
+            ("0000000000000000000000000000000000000000000000000000000000000003",
+             "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+             "0000000000000000000000000000000000000000000000000000000000000000",
+             "0000000000000000000000000000000000000000000000000000000000000000",
+             "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0"),
+            ("B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+             "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+             "0000000000000000000000000000000000000000000000000000000000000001",
+             "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+             "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A"),
+            ("C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9",
+             "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8",
+             "C87AA53824B4D7AE2EB035A2B5BBBCCC080E76CDC6D1692C4B0B62D798E6D906",
+             "7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C",
+             "5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1BAB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7"),
+            ("0B432B2677937381AEF05BB02A66ECD012773062CF3FA2549E44F58ED2401710",
+             "25D1DFF95105F5253C4022F628A996AD3A0D95FBF21D468A1B33F8C160D8F517",
+             "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+             "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+             "7EB0509757E246F19449885651611CB965ECC1A187DD51B64FDA1EDC9637D5EC97582B9CB13DB3933705B32BA982AF5AF25FD78881EBB32771FC5922EFC66EA3"),
+        ];
+        for &(secret,public,aux,msg,sig) in vectors.iter() {
+            let secret = Secret::from_slice(&unhex(secret)).unwrap();
+            let public = Public::from_slice(&unhex(public)).unwrap();
+            let mut raw = [0u8;BYTES];
+            raw.copy_from_slice(&unhex(aux));
+            let (msg, sig) = (unhex(msg), Signature::from_slice(&unhex(sig)).unwrap());
+            assert_eq!(secret.public().unwrap(),public);
+            assert!(secret.sign_with_aux(&msg,&raw).unwrap() == sig);
+            public.verify(&msg,&sig).unwrap();
+        }
+    }
+
+    #[test]
+    fn bip340_verify_edge() {
+        // bip-340 test vector 4 (an `R.x` with many leading zeros).
+        let public = Public::from_slice(&unhex("D69C3509BB99E412E68B0FE8544E72837DFA30746D8BE2AA65975F29D22DC7B9")).unwrap();
+        let msg = unhex("4DF3C3F68FCC83B27E9D42C90431A72499F17875C81A599B566C9889B9696703");
+        let sig = Signature::from_slice(&unhex("00000000000000000000003B78CE563F89A0ED9414F5AA28AD0D96D6795F9C6376AFB1548AF603B3EB45C9F8207DEE1060CB71C04E80F593060B07D28308D7F4")).unwrap();
+        public.verify(&msg,&sig).unwrap();
+    }
+
+    #[test]
+    fn verify_err_kinds() {
+        let pair = KeyPair::generate().unwrap();
+        let sig = pair.sign(b"msg").unwrap();
+        // `s` must be below the group order, & `r` below the field prime.
+        for half in 0..2 {
+            let mut raw = [0u8;BYTES*2];
+            raw.copy_from_slice(sig.as_ref());
+            for byte in raw[half*BYTES..(half+1)*BYTES].iter_mut() { *byte = 0xff; }
+            assert_eq!(pair.public().verify(b"msg",&Signature::from(raw)),Err(Error::MalformedSignature));
+        }
+        // bip-340 test vector 5: `x = eefdea...4a34` isn't on the curve.
+        let bad = Public::from_slice(&unhex("EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34")).unwrap();
+        assert_eq!(bad.verify(b"msg",&sig),Err(Error::InvalidKey));
+        // the secret scalar must lie in `[1,n)`.
+        assert_eq!(Secret::default().public(),Err(Error::InvalidSecretKey));
+        assert_eq!(Secret::from([0xff;BYTES]).sign(b"msg").err(),Some(Error::InvalidSecretKey));
+    }
+}
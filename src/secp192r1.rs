@@ -0,0 +1,119 @@
+//! types and functions for signing operations on the `secp192r1` curve.
+//!
+//! only built with the `secp192r1` feature.  small curves like this one offer
+//! little security margin, & are provided for talking to constrained devices.
+use libc::{uint8_t,c_int};
+use arith::Params;
+use error::Error;
+use portable;
+
+
+/// size of curve.
+const BYTES: usize = 24;
+
+
+/// domain parameters of `secp192r1` (as in `easy-ecc`'s `Curve_*_24` defs).
+const PARAMS: Params = Params {
+    p: &[0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFE, 0xFFFFFFFFFFFFFFFF],
+    a: &[0xFFFFFFFFFFFFFFFC, 0xFFFFFFFFFFFFFFFE, 0xFFFFFFFFFFFFFFFF],
+    b: &[0xFEB8DEECC146B9B1, 0x0FA7E9AB72243049, 0x64210519E59C80E7],
+    gx: &[0xF4FF0AFD82FF1012, 0x7CBF20EB43A18800, 0x188DA80EB03090F6],
+    gy: &[0x73F977A11E794811, 0x631011ED6B24CDD5, 0x07192B95FFC8DA78],
+    n: &[0x146BC9B1B4D22831, 0xFFFFFFFF99DEF836, 0xFFFFFFFFFFFFFFFF],
+};
+
+
+/// der encoding of the curve's object identifier (`1.2.840.10045.3.1.1`).
+const OID: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x01];
+
+
+/// curve name used in json web keys.  no name is registered with iana for
+/// this curve, so the sec name is used.
+const JWK_CRV: &str = "secp192r1";
+
+
+impl_curve!(Secp192r1,"secp192r1",::sha2::Sha256);
+
+
+// backend functions for `impl_curve!`.  verification calls into `easy-ecc`;
+// keygen, signing & ecdh (which draw randomness) use the portable arithmetic,
+// so that all randomness comes from `random`.
+fn backend_keygen(public: &mut Public, secret: &mut Secret) -> Result<(),Error> {
+    portable::keygen(&PARAMS,&mut public.0,&mut secret.0)
+}
+
+
+fn backend_sign(key: &Secret, msg: &[u8;BYTES]) -> Result<Signature,Error> {
+    let mut sig = Signature::default();
+    portable::sign(&PARAMS,&key.0,msg,&mut sig.0)?;
+    Ok(sig)
+}
+
+
+fn backend_verify(key: &Public, msg: &[u8;BYTES], sig: &Signature) -> Result<(),Error> {
+    let rslt = unsafe {
+        ecdsa_verify(&key.0 as *const [u8;BYTES+1], msg as *const [u8;BYTES], &sig.0 as *const [u8;BYTES*2])
+    };
+    match rslt {
+        1 => Ok(()),
+        _ => Err(Error::InvalidSignature)
+    }
+}
+
+
+fn backend_ecdh(public: &Public, secret: &Secret) -> Result<SharedSecret,Error> {
+    let mut shared = SharedSecret::default();
+    portable::ecdh(&PARAMS,&public.0,&secret.0,&mut shared.0)?;
+    Ok(shared)
+}
+
+
+// ffi function defs (built with `ECC_CURVE=secp192r1` & prefixed symbols).
+#[link(name = "p192", kind = "static")]
+extern "C" {
+    #[link_name = "secp192r1_ecdsa_verify"]
+    fn ecdsa_verify(p_publicKey: *const [uint8_t;BYTES+1], p_hash: *const [uint8_t;BYTES], p_signature: *const [uint8_t;BYTES*2]) -> c_int;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use secp192r1::{BYTES,Prehash,Public,Secret,Signature,KeyPair,verify,ecdh};
+    use error::Error;
+
+    #[test]
+    #[allow(deprecated)]
+    fn keygen_ok() {
+        use secp192r1::keygen;
+        let mut public = Public::default();
+        let mut secret = Secret::default();
+        keygen(&mut public, &mut secret).unwrap();
+
+        assert!(public != Public::default());
+        assert!(secret != Secret::default());
+        assert!(secret.public().unwrap() == public);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn signing_ok() {
+        use secp192r1::sign;
+        let pair = KeyPair::generate().unwrap();
+        let mut sig = Signature::default();
+        let mut msg = [0u8;BYTES];
+        msg[0] = 1; msg[2] = 3; msg[4] = 5;
+        let hash = Prehash::from_hash(&msg).unwrap();
+        sign(pair.secret(),&msg,&mut sig).unwrap();
+        verify(pair.public(),&hash,&sig).unwrap();
+        msg[0] ^= 0xff;
+        assert_eq!(verify(pair.public(),&Prehash::from_hash(&msg).unwrap(),&sig),Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn ecdh_ok() {
+        let (pair_a, pair_b) = (KeyPair::generate().unwrap(), KeyPair::generate().unwrap());
+        let shared_a = ecdh(pair_b.public(),pair_a.secret()).unwrap();
+        let shared_b = ecdh(pair_a.public(),pair_b.secret()).unwrap();
+        assert_eq!(shared_a,shared_b);
+    }
+}
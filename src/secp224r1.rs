@@ -0,0 +1,114 @@
+//! types and functions for signing operations on the `secp224r1` curve.
+//!
+//! `easy-ecc` only builds for `secp128r1`, `secp192r1`, `secp256r1` &
+//! `secp384r1`, so this module is backed by the crate's portable arithmetic.
+use arith::Params;
+use error::Error;
+use portable;
+
+
+/// size of curve.
+const BYTES: usize = 28;
+
+
+/// domain parameters of `secp224r1`.
+const PARAMS: Params = Params {
+    p: &[0x0000000000000001, 0xFFFFFFFF00000000, 0xFFFFFFFFFFFFFFFF, 0x00000000FFFFFFFF],
+    a: &[0xFFFFFFFFFFFFFFFE, 0xFFFFFFFEFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0x00000000FFFFFFFF],
+    b: &[0x270B39432355FFB4, 0x5044B0B7D7BFD8BA, 0x0C04B3ABF5413256, 0x00000000B4050A85],
+    gx: &[0x343280D6115C1D21, 0x4A03C1D356C21122, 0x6BB4BF7F321390B9, 0x00000000B70E0CBD],
+    gy: &[0x44D5819985007E34, 0xCD4375A05A074764, 0xB5F723FB4C22DFE6, 0x00000000BD376388],
+    n: &[0x13DD29455C5C2A3D, 0xFFFF16A2E0B8F03E, 0xFFFFFFFFFFFFFFFF, 0x00000000FFFFFFFF],
+};
+
+
+/// der encoding of the curve's object identifier (`1.3.132.0.33`).
+const OID: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x21];
+
+
+/// curve name used in json web keys.  no name is registered with iana for
+/// this curve, so the sec name is used.
+const JWK_CRV: &str = "secp224r1";
+
+
+impl_curve!(Secp224r1,"secp224r1",::sha2::Sha224);
+
+
+// backend functions for `impl_curve!`, using the portable arithmetic.
+fn backend_keygen(public: &mut Public, secret: &mut Secret) -> Result<(),Error> {
+    portable::keygen(&PARAMS,&mut public.0,&mut secret.0)
+}
+
+
+fn backend_sign(key: &Secret, msg: &[u8;BYTES]) -> Result<Signature,Error> {
+    let mut sig = Signature::default();
+    portable::sign(&PARAMS,&key.0,msg,&mut sig.0)?;
+    Ok(sig)
+}
+
+
+fn backend_verify(key: &Public, msg: &[u8;BYTES], sig: &Signature) -> Result<(),Error> {
+    portable::verify(&PARAMS,&key.0,msg,&sig.0)
+}
+
+
+fn backend_ecdh(public: &Public, secret: &Secret) -> Result<SharedSecret,Error> {
+    let mut shared = SharedSecret::default();
+    portable::ecdh(&PARAMS,&public.0,&secret.0,&mut shared.0)?;
+    Ok(shared)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use secp224r1::{BYTES,Prehash,Public,Secret,Signature,KeyPair,verify,ecdh};
+    use error::Error;
+
+    #[test]
+    #[allow(deprecated)]
+    fn keygen_ok() {
+        use secp224r1::keygen;
+        let mut public = Public::default();
+        let mut secret = Secret::default();
+        keygen(&mut public, &mut secret).unwrap();
+
+        assert!(public != Public::default());
+        assert!(secret != Secret::default());
+        assert!(secret.public().unwrap() == public);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn signing_ok() {
+        use secp224r1::sign;
+        let pair = KeyPair::generate().unwrap();
+        let mut sig = Signature::default();
+        let mut msg = [0u8;BYTES];
+        msg[0] = 1; msg[2] = 3; msg[4] = 5;
+        let hash = Prehash::from_hash(&msg).unwrap();
+        sign(pair.secret(),&msg,&mut sig).unwrap();
+        verify(pair.public(),&hash,&sig).unwrap();
+        msg[0] ^= 0xff;
+        assert_eq!(verify(pair.public(),&Prehash::from_hash(&msg).unwrap(),&sig),Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn ecdh_ok() {
+        let (pair_a, pair_b) = (KeyPair::generate().unwrap(), KeyPair::generate().unwrap());
+        let shared_a = ecdh(pair_b.public(),pair_a.secret()).unwrap();
+        let shared_b = ecdh(pair_a.public(),pair_b.secret()).unwrap();
+        assert_eq!(shared_a,shared_b);
+    }
+
+    #[test]
+    fn generator_ok() {
+        let mut one = [0u8;BYTES];
+        one[BYTES-1] = 1;
+        assert!(Secret::from(one).public().unwrap() == Public::from([
+            0x02, 0xb7, 0x0e, 0x0c, 0xbd, 0x6b, 0xb4, 0xbf,
+            0x7f, 0x32, 0x13, 0x90, 0xb9, 0x4a, 0x03, 0xc1,
+            0xd3, 0x56, 0xc2, 0x11, 0x22, 0x34, 0x32, 0x80,
+            0xd6, 0x11, 0x5c, 0x1d, 0x21
+        ]));
+    }
+}
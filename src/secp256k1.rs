@@ -0,0 +1,142 @@
+//! types and functions for signing operations on the `secp256k1` curve.
+//!
+//! `easy-ecc` doesn't support `secp256k1`, so unlike the other curve modules
+//! this one is backed entirely by the crate's portable arithmetic.
+use arith::Params;
+use error::Error;
+use portable;
+
+
+/// size of curve.
+const BYTES: usize = 32;
+
+
+/// domain parameters of `secp256k1`.
+pub(crate) const PARAMS: Params = Params {
+    p: &[0xFFFFFFFEFFFFFC2F, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF],
+    a: &[0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    b: &[0x0000000000000007, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    gx: &[0x59F2815B16F81798, 0x029BFCDB2DCE28D9, 0x55A06295CE870B07, 0x79BE667EF9DCBBAC],
+    gy: &[0x9C47D08FFB10D4B8, 0xFD17B448A6855419, 0x5DA4FBFC0E1108A8, 0x483ADA7726A3C465],
+    n: &[0xBFD25E8CD0364141, 0xBAAEDCE6AF48A03B, 0xFFFFFFFFFFFFFFFE, 0xFFFFFFFFFFFFFFFF],
+};
+
+
+/// der encoding of the curve's object identifier (`1.3.132.0.10`).
+const OID: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x0a];
+
+
+/// curve name used in json web keys (rfc8812 §3.1).
+const JWK_CRV: &str = "secp256k1";
+
+
+impl_curve!(Secp256k1,"secp256k1",::sha2::Sha256);
+impl_cose!(8);
+impl_multicodec!(0xe7);
+impl_signature!();
+
+
+// backend functions for `impl_curve!`, using the portable arithmetic.
+fn backend_keygen(public: &mut Public, secret: &mut Secret) -> Result<(),Error> {
+    portable::keygen(&PARAMS,&mut public.0,&mut secret.0)
+}
+
+
+fn backend_sign(key: &Secret, msg: &[u8;BYTES]) -> Result<Signature,Error> {
+    let mut sig = Signature::default();
+    portable::sign(&PARAMS,&key.0,msg,&mut sig.0)?;
+    Ok(sig)
+}
+
+
+fn backend_verify(key: &Public, msg: &[u8;BYTES], sig: &Signature) -> Result<(),Error> {
+    portable::verify(&PARAMS,&key.0,msg,&sig.0)
+}
+
+
+fn backend_ecdh(public: &Public, secret: &Secret) -> Result<SharedSecret,Error> {
+    let mut shared = SharedSecret::default();
+    portable::ecdh(&PARAMS,&public.0,&secret.0,&mut shared.0)?;
+    Ok(shared)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use secp256k1::{BYTES,Prehash,Public,Secret,Signature,RecoverableSignature,KeyPair,verify,ecdh};
+    use error::Error;
+
+    #[test]
+    #[allow(deprecated)]
+    fn keygen_ok() {
+        use secp256k1::keygen;
+        let mut public = Public::default();
+        let mut secret = Secret::default();
+        keygen(&mut public, &mut secret).unwrap();
+
+        assert!(public != Public::default());
+        assert!(secret != Secret::default());
+        assert!(secret.public().unwrap() == public);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn signing_ok() {
+        use secp256k1::sign;
+        let pair = KeyPair::generate().unwrap();
+        let mut sig = Signature::default();
+        let mut msg = [0u8;BYTES];
+        msg[0] = 1; msg[2] = 3; msg[4] = 5;
+        let hash = Prehash::from_hash(&msg).unwrap();
+        sign(pair.secret(),&msg,&mut sig).unwrap();
+        verify(pair.public(),&hash,&sig).unwrap();
+        msg[0] ^= 0xff;
+        assert_eq!(verify(pair.public(),&Prehash::from_hash(&msg).unwrap(),&sig),Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn ecdh_ok() {
+        let (pair_a, pair_b) = (KeyPair::generate().unwrap(), KeyPair::generate().unwrap());
+        let shared_a = ecdh(pair_b.public(),pair_a.secret()).unwrap();
+        let shared_b = ecdh(pair_a.public(),pair_b.secret()).unwrap();
+        assert_eq!(shared_a,shared_b);
+    }
+
+    #[test]
+    fn generator_multiples_ok() {
+        let mut one = [0u8;BYTES];
+        one[BYTES-1] = 1;
+        assert!(Secret::from(one).public().unwrap() == Public::from([
+            0x02, 0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb,
+            0xac, 0x55, 0xa0, 0x62, 0x95, 0xce, 0x87, 0x0b,
+            0x07, 0x02, 0x9b, 0xfc, 0xdb, 0x2d, 0xce, 0x28,
+            0xd9, 0x59, 0xf2, 0x81, 0x5b, 0x16, 0xf8, 0x17,
+            0x98
+        ]));
+        let mut two = [0u8;BYTES];
+        two[BYTES-1] = 2;
+        assert!(Secret::from(two).public().unwrap() == Public::from([
+            0x02, 0xc6, 0x04, 0x7f, 0x94, 0x41, 0xed, 0x7d,
+            0x6d, 0x30, 0x45, 0x40, 0x6e, 0x95, 0xc0, 0x7c,
+            0xd8, 0x5c, 0x77, 0x8e, 0x4b, 0x8c, 0xef, 0x3c,
+            0xa7, 0xab, 0xac, 0x09, 0xb9, 0x5c, 0x70, 0x9e,
+            0xe5
+        ]));
+    }
+
+    #[test]
+    fn recover_eip155_ok() {
+        // the example transaction of eip-155 (`v = 37`, so recovery id 0),
+        // signed by the key `0x4646..46`.
+        use hex::unhex;
+        let secret = Secret::from_slice(&[0x46;BYTES]).unwrap();
+        let msg = Prehash::from_hash(&unhex("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53")).unwrap();
+        let sig = Signature::from_slice(&unhex(concat!(
+            "28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276",
+            "67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"))).unwrap();
+        let public = Public::recover_from(&msg,&RecoverableSignature::new(&sig,0).unwrap()).unwrap();
+        assert!(public == secret.public().unwrap());
+        assert_eq!(&public.0[..],&unhex("024bc2a31265153f07e70e0bab08724e6b85e217f8cd628ceb62974247bb493382")[..]);
+        verify(&public,&msg,&sig).unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+//! types and functions for signing operations on the `secp384r1` curve.
+use libc::{uint8_t,c_int};
+use arith::Params;
+use error::Error;
+use portable;
+
+
+/// size of curve.
+const BYTES: usize = 48;
+
+
+/// domain parameters of `secp384r1` (as in `easy-ecc`'s `Curve_*_48` defs).
+const PARAMS: Params = Params {
+    p: &[0x00000000FFFFFFFF, 0xFFFFFFFF00000000, 0xFFFFFFFFFFFFFFFE, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF],
+    a: &[0x00000000FFFFFFFC, 0xFFFFFFFF00000000, 0xFFFFFFFFFFFFFFFE, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF],
+    b: &[0x2A85C8EDD3EC2AEF, 0xC656398D8A2ED19D, 0x0314088F5013875A, 0x181D9C6EFE814112, 0x988E056BE3F82D19, 0xB3312FA7E23EE7E4],
+    gx: &[0x3A545E3872760AB7, 0x5502F25DBF55296C, 0x59F741E082542A38, 0x6E1D3B628BA79B98, 0x8EB1C71EF320AD74, 0xAA87CA22BE8B0537],
+    gy: &[0x7A431D7C90EA0E5F, 0x0A60B1CE1D7E819D, 0xE9DA3113B5F0B8C0, 0xF8F41DBD289A147C, 0x5D9E98BF9292DC29, 0x3617DE4A96262C6F],
+    n: &[0xECEC196ACCC52973, 0x581A0DB248B0A77A, 0xC7634D81F4372DDF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF],
+};
+
+
+/// der encoding of the curve's object identifier (`1.3.132.0.34`).
+const OID: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x22];
+
+
+/// curve name used in json web keys (rfc7518 §6.2.1.1).
+const JWK_CRV: &str = "P-384";
+
+
+impl_curve!(Secp384r1,"secp384r1",::sha2::Sha384);
+impl_openssh!("nistp384");
+impl_cose!(2);
+impl_multicodec!(0x1201);
+impl_signature!();
+
+
+// backend functions for `impl_curve!`.  verification calls into `easy-ecc`;
+// keygen, signing & ecdh (which draw randomness) use the portable arithmetic,
+// so that all randomness comes from `random`.
+fn backend_keygen(public: &mut Public, secret: &mut Secret) -> Result<(),Error> {
+    portable::keygen(&PARAMS,&mut public.0,&mut secret.0)
+}
+
+
+fn backend_sign(key: &Secret, msg: &[u8;BYTES]) -> Result<Signature,Error> {
+    let mut sig = Signature::default();
+    portable::sign(&PARAMS,&key.0,msg,&mut sig.0)?;
+    Ok(sig)
+}
+
+
+fn backend_verify(key: &Public, msg: &[u8;BYTES], sig: &Signature) -> Result<(),Error> {
+    let rslt = unsafe {
+        ecdsa_verify(&key.0 as *const [u8;BYTES+1], msg as *const [u8;BYTES], &sig.0 as *const [u8;BYTES*2])
+    };
+    match rslt {
+        1 => Ok(()),
+        _ => Err(Error::InvalidSignature)
+    }
+}
+
+
+fn backend_ecdh(public: &Public, secret: &Secret) -> Result<SharedSecret,Error> {
+    let mut shared = SharedSecret::default();
+    portable::ecdh(&PARAMS,&public.0,&secret.0,&mut shared.0)?;
+    Ok(shared)
+}
+
+
+// ffi function defs (built with `ECC_CURVE=secp384r1` & prefixed symbols).
+#[link(name = "p384", kind = "static")]
+extern "C" {
+    #[link_name = "secp384r1_ecdsa_verify"]
+    fn ecdsa_verify(p_publicKey: *const [uint8_t;BYTES+1], p_hash: *const [uint8_t;BYTES], p_signature: *const [uint8_t;BYTES*2]) -> c_int;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use secp384r1::{BYTES,Prehash,Public,Secret,Signature,KeyPair,verify,ecdh};
+    use error::Error;
+
+    #[test]
+    #[allow(deprecated)]
+    fn keygen_ok() {
+        use secp384r1::keygen;
+        let mut public = Public::default();
+        let mut secret = Secret::default();
+        keygen(&mut public, &mut secret).unwrap();
+
+        assert!(public != Public::default());
+        assert!(secret != Secret::default());
+        assert!(secret.public().unwrap() == public);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn signing_ok() {
+        use secp384r1::sign;
+        let pair = KeyPair::generate().unwrap();
+        let mut sig = Signature::default();
+        let mut msg = [0u8;BYTES];
+        msg[0] = 1; msg[2] = 3; msg[4] = 5;
+        let hash = Prehash::from_hash(&msg).unwrap();
+        sign(pair.secret(),&msg,&mut sig).unwrap();
+        verify(pair.public(),&hash,&sig).unwrap();
+        msg[0] ^= 0xff;
+        assert_eq!(verify(pair.public(),&Prehash::from_hash(&msg).unwrap(),&sig),Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    #[cfg(feature = "signature")]
+    fn sign_msg_ok() {
+        use signature::{Signer,Verifier};
+        // messages are hashed with sha-384 both here & by the traits.
+        let pair = KeyPair::generate().unwrap();
+        let sig = pair.sign_msg(b"msg").unwrap();
+        Verifier::verify(pair.public(),b"msg",&sig).unwrap();
+        let sig: Signature = Signer::sign(&pair,b"msg");
+        pair.public().verify_msg(b"msg",&sig).unwrap();
+        assert_eq!(pair.public().verify_msg(b"other",&sig),Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn recover_err() {
+        use secp384r1::{RecoverableSignature,SIGNATURE_SIZE};
+        // with `r = n - 1`, the x-coordinate `r + n` for recovery ids 2 & 3
+        // overflows 384 bits.
+        let mut bytes = [0u8;SIGNATURE_SIZE + 1];
+        bytes[..BYTES].copy_from_slice(&[
+            0xff,0xff,0xff,0xff,0xff,0xff,0xff,0xff,0xff,0xff,0xff,0xff,0xff,0xff,0xff,0xff,
+            0xff,0xff,0xff,0xff,0xff,0xff,0xff,0xff,0xc7,0x63,0x4d,0x81,0xf4,0x37,0x2d,0xdf,
+            0x58,0x1a,0x0d,0xb2,0x48,0xb0,0xa7,0x7a,0xec,0xec,0x19,0x6a,0xcc,0xc5,0x29,0x72]);
+        bytes[SIGNATURE_SIZE - 1] = 1;
+        let msg = Prehash::from_sha256(b"msg");
+        for id in 2..4 {
+            bytes[SIGNATURE_SIZE] = id;
+            let sig = RecoverableSignature::from_bytes(bytes);
+            assert_eq!(Public::recover_from(&msg,&sig).err(),Some(Error::InvalidSignature));
+        }
+    }
+
+    #[test]
+    fn ecdh_ok() {
+        let (pair_a, pair_b) = (KeyPair::generate().unwrap(), KeyPair::generate().unwrap());
+        let shared_a = ecdh(pair_b.public(),pair_a.secret()).unwrap();
+        let shared_b = ecdh(pair_a.public(),pair_b.secret()).unwrap();
+        assert_eq!(shared_a,shared_b);
+    }
+}
@@ -0,0 +1,125 @@
+//! types and functions for signing operations on the `secp521r1` curve.
+//!
+//! `easy-ecc` doesn't implement `secp521r1`, so this module is backed by the
+//! crate's portable arithmetic, and is only built with the `secp521r1`
+//! feature (which also widens the arithmetic, slowing the other curves).
+//! scalars & coordinates are 66 bytes, the top 7 bits of which are zero.
+use arith::Params;
+use error::Error;
+use portable;
+
+
+/// size of curve (521 bits, padded out to whole bytes).
+const BYTES: usize = 66;
+
+
+/// domain parameters of `secp521r1`.
+const PARAMS: Params = Params {
+    p: &[0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0x00000000000001FF],
+    a: &[0xFFFFFFFFFFFFFFFC, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0x00000000000001FF],
+    b: &[0xEF451FD46B503F00, 0x3573DF883D2C34F1, 0x1652C0BD3BB1BF07, 0x56193951EC7E937B, 0xB8B489918EF109E1, 0xA2DA725B99B315F3, 0x929A21A0B68540EE, 0x953EB9618E1C9A1F, 0x0000000000000051],
+    gx: &[0xF97E7E31C2E5BD66, 0x3348B3C1856A429B, 0xFE1DC127A2FFA8DE, 0xA14B5E77EFE75928, 0xF828AF606B4D3DBA, 0x9C648139053FB521, 0x9E3ECB662395B442, 0x858E06B70404E9CD, 0x00000000000000C6],
+    gy: &[0x88BE94769FD16650, 0x353C7086A272C240, 0xC550B9013FAD0761, 0x97EE72995EF42640, 0x17AFBD17273E662C, 0x98F54449579B4468, 0x5C8A5FB42C7D1BD9, 0x39296A789A3BC004, 0x0000000000000118],
+    n: &[0xBB6FB71E91386409, 0x3BB5C9B8899C47AE, 0x7FCC0148F709A5D0, 0x51868783BF2F966B, 0xFFFFFFFFFFFFFFFA, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0x00000000000001FF],
+};
+
+
+/// der encoding of the curve's object identifier (`1.3.132.0.35`).
+const OID: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x23];
+
+
+/// curve name used in json web keys (rfc7518 §6.2.1.1).
+const JWK_CRV: &str = "P-521";
+
+
+impl_curve!(Secp521r1,"secp521r1",::sha2::Sha512);
+impl_openssh!("nistp521");
+impl_cose!(3);
+impl_multicodec!(0x1202);
+
+
+// backend functions for `impl_curve!`, using the portable arithmetic.
+fn backend_keygen(public: &mut Public, secret: &mut Secret) -> Result<(),Error> {
+    portable::keygen(&PARAMS,&mut public.0,&mut secret.0)
+}
+
+
+fn backend_sign(key: &Secret, msg: &[u8;BYTES]) -> Result<Signature,Error> {
+    let mut sig = Signature::default();
+    portable::sign(&PARAMS,&key.0,msg,&mut sig.0)?;
+    Ok(sig)
+}
+
+
+fn backend_verify(key: &Public, msg: &[u8;BYTES], sig: &Signature) -> Result<(),Error> {
+    portable::verify(&PARAMS,&key.0,msg,&sig.0)
+}
+
+
+fn backend_ecdh(public: &Public, secret: &Secret) -> Result<SharedSecret,Error> {
+    let mut shared = SharedSecret::default();
+    portable::ecdh(&PARAMS,&public.0,&secret.0,&mut shared.0)?;
+    Ok(shared)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use secp521r1::{BYTES,Prehash,Public,Secret,Signature,KeyPair,verify,ecdh};
+    use error::Error;
+    use hex::unhex;
+
+    #[test]
+    #[allow(deprecated)]
+    fn keygen_ok() {
+        use secp521r1::keygen;
+        let mut public = Public::default();
+        let mut secret = Secret::default();
+        keygen(&mut public, &mut secret).unwrap();
+
+        assert!(public != Public::default());
+        assert!(secret != Secret::default());
+        assert!(secret.public().unwrap() == public);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn signing_ok() {
+        use secp521r1::sign;
+        let pair = KeyPair::generate().unwrap();
+        let mut sig = Signature::default();
+        let mut msg = [0u8;BYTES];
+        msg[0] = 1; msg[2] = 3; msg[4] = 5;
+        let hash = Prehash::from_hash(&msg).unwrap();
+        sign(pair.secret(),&msg,&mut sig).unwrap();
+        verify(pair.public(),&hash,&sig).unwrap();
+        msg[0] ^= 0xff;
+        assert_eq!(verify(pair.public(),&Prehash::from_hash(&msg).unwrap(),&sig),Err(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn prehash_ok() {
+        use sha2::{Sha512,Digest};
+        // a sha-512 signature (shorter than the curve size, so it isn't
+        // truncated) under the key of rfc6979 appendix a.2.7.
+        let public = Public::from_slice(&unhex("0301894550d0785932e00eaa23b694f213f8c3121f86dc97a04e5a7167db4e5bcd371123d46e45db6b5d5370a7f20fb633155d38ffa16d2bd761dcac474b9a2f5023a4")).unwrap();
+        let sig = Signature::from_slice(&unhex(concat!(
+            "0047ea6b159a4e677ffe6c45094ea76ef70f85100288a9a5a69b2e9abea85562bed2093eefcc6d03a679fd0e84802da8398749395fb837ae2bf2714ef93dee3f96ec",
+            "010a8c29d3e10f24994685bb37d7ad4f9d4fcc6b3a07ce86f57e3941e1ae76cb2e362aa9c3e66e551d479ce444b7ca9b7755e477d2a7361dc40c5cf77b59dce59540"))).unwrap();
+        public.verify_prehash(&Sha512::digest(b"sample"),&sig).unwrap();
+        public.verify_digest(Sha512::new().chain_update(b"sample"),&sig).unwrap();
+        assert_eq!(public.verify_prehash(&Sha512::digest(b"other"),&sig),Err(Error::InvalidSignature));
+        let pair = KeyPair::generate().unwrap();
+        let sig = pair.sign_digest(Sha512::new().chain_update(b"sample")).unwrap();
+        pair.public().verify_prehash(&Sha512::digest(b"sample"),&sig).unwrap();
+        assert_eq!(Prehash::from_hash(&[0xaa;32]).err(),Some(Error::WrongLength { expected: 33, got: 32 }));
+    }
+
+    #[test]
+    fn ecdh_ok() {
+        let (pair_a, pair_b) = (KeyPair::generate().unwrap(), KeyPair::generate().unwrap());
+        let shared_a = ecdh(pair_b.public(),pair_a.secret()).unwrap();
+        let shared_b = ecdh(pair_a.public(),pair_b.secret()).unwrap();
+        assert_eq!(shared_a,shared_b);
+    }
+}
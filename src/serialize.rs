@@ -0,0 +1,176 @@
+//! serde support for byte array newtypes (keys, signatures & the like).
+//! only built with the `serde` feature.
+//!
+//! by default, human-readable formats (such as json) get a lowercase hex
+//! string, while binary formats (such as bincode or cbor) get the raw
+//! bytes, avoiding the doubled size of hex.  the `hex` & `bytes` adapters
+//! pin one encoding for a field regardless of the format:
+//!
+//! ```ignore
+//! #[derive(Serialize,Deserialize)]
+//! struct Record {
+//!     #[serde(with = "easy_ecc::serialize::hex")]
+//!     key: Public,
+//!     #[serde(with = "easy_ecc::serialize::bytes")]
+//!     sig: Signature,
+//! }
+//! ```
+//!
+//! deserializing hex accepts either case & an optional `0x` prefix;
+//! deserializing bytes also accepts a sequence of integers, for formats
+//! which encode byte strings that way.
+use std::fmt;
+use serde::{Serializer,Deserializer};
+use serde::de::{self,Visitor,SeqAccess,Unexpected};
+use error::Error;
+
+
+/// a byte array newtype which the `hex` & `bytes` adapters can serialize.
+/// it's implemented by every type with serde support, & can't be
+/// implemented outside this crate.
+pub trait ByteArray: sealed::Sealed { }
+
+
+pub(crate) mod sealed {
+    /// access to a byte array newtype's bytes, kept private so secrets stay
+    /// behind their `expose_secret` accessors.
+    pub trait Sealed: Sized {
+        /// the all-zero value.
+        fn zeroed() -> Self;
+        /// the bytes.
+        fn bytes(&self) -> &[u8];
+        /// the bytes, mutably.
+        fn bytes_mut(&mut self) -> &mut [u8];
+    }
+}
+
+
+/// serde adapter encoding a byte array newtype as a hex string in every
+/// format.
+pub mod hex {
+    use serde::{Serializer,Deserializer};
+    use serialize::{ByteArray,HexVisitor,finish};
+
+    /// serialize `value` as a lowercase hex string.
+    pub fn serialize<T: ByteArray, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok,S::Error> {
+        serializer.serialize_str(&::hex::encode(value.bytes()))
+    }
+
+    /// deserialize a hex string of exactly the right length.
+    pub fn deserialize<'de, T: ByteArray, D: Deserializer<'de>>(deserializer: D) -> Result<T,D::Error> {
+        let mut value = T::zeroed();
+        let rslt = deserializer.deserialize_str(HexVisitor(value.bytes_mut()));
+        finish(value,rslt)
+    }
+}
+
+
+/// serde adapter encoding a byte array newtype as raw bytes in every
+/// format.
+pub mod bytes {
+    use serde::{Serializer,Deserializer};
+    use serialize::{ByteArray,BytesVisitor,finish};
+
+    /// serialize `value` as a byte string.
+    pub fn serialize<T: ByteArray, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok,S::Error> {
+        serializer.serialize_bytes(value.bytes())
+    }
+
+    /// deserialize a byte string (or sequence of bytes) of exactly the
+    /// right length.
+    pub fn deserialize<'de, T: ByteArray, D: Deserializer<'de>>(deserializer: D) -> Result<T,D::Error> {
+        let mut value = T::zeroed();
+        let rslt = deserializer.deserialize_bytes(BytesVisitor(value.bytes_mut()));
+        finish(value,rslt)
+    }
+}
+
+
+/// serialize `value` in the form suiting `serializer`.
+pub(crate) fn serialize<T: ByteArray, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok,S::Error> {
+    if serializer.is_human_readable() {
+        hex::serialize(value,serializer)
+    } else {
+        bytes::serialize(value,serializer)
+    }
+}
+
+
+/// return a deserialized `value`, or wipe the partly filled value (which
+/// may hold part of a secret key) & return the error.
+fn finish<T: ByteArray, E>(mut value: T, rslt: Result<(),E>) -> Result<T,E> {
+    match rslt {
+        Ok(()) => Ok(value),
+        Err(err) => {
+            ::arith::wipe_bytes(value.bytes_mut());
+            Err(err)
+        }
+    }
+}
+
+
+/// deserialize a value in the form suiting `deserializer`.
+pub(crate) fn deserialize<'de, T: ByteArray, D: Deserializer<'de>>(deserializer: D) -> Result<T,D::Error> {
+    if deserializer.is_human_readable() {
+        hex::deserialize(deserializer)
+    } else {
+        bytes::deserialize(deserializer)
+    }
+}
+
+
+/// visitor filling a buffer from a hex string.
+struct HexVisitor<'a>(&'a mut [u8]);
+
+impl<'a, 'de> Visitor<'de> for HexVisitor<'a> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"a hex string of {} bytes",self.0.len())
+    }
+
+    // the rejected string is kept out of the error, since it may be a
+    // (nearly valid) secret key.
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<(),E> {
+        match ::hex::decode(v,&mut *self.0) {
+            Ok(()) => Ok(()),
+            Err(Error::WrongLength { got, .. }) => Err(E::invalid_length(got,&self)),
+            Err(_) => Err(E::invalid_value(Unexpected::Other("malformed hex"),&self)),
+        }
+    }
+}
+
+
+/// visitor filling a buffer from a byte string (or sequence of bytes).
+struct BytesVisitor<'a>(&'a mut [u8]);
+
+impl<'a, 'de> Visitor<'de> for BytesVisitor<'a> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{} bytes",self.0.len())
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<(),E> {
+        if v.len() != self.0.len() {
+            return Err(E::invalid_length(v.len(),&self));
+        }
+        self.0.copy_from_slice(v);
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(),A::Error> {
+        let mut len = 0;
+        while let Some(byte) = seq.next_element::<u8>()? {
+            if len == self.0.len() {
+                return Err(de::Error::invalid_length(len + 1,&self));
+            }
+            self.0[len] = byte;
+            len += 1;
+        }
+        if len != self.0.len() {
+            return Err(de::Error::invalid_length(len,&self));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,325 @@
+//! slip-0010 hierarchical deterministic keys over `secp256r1`, for trees of
+//! device or service identities derived from one seed.
+//!
+//! slip-0010 carries bip-32 (see `hd`) over to other curves.  for
+//! `secp256r1` it differs in two ways: the master key is derived with the
+//! hmac key `"Nist256p1 seed"`, & a derivation which lands on an invalid key
+//! is retried (re-hashing until a valid key results) rather than failing,
+//! since the larger gap between `2^256` & the group order makes that
+//! likely enough to matter.  paths are `hd::DerivationPath`s, & there's no
+//! standard string serialization.
+use std::fmt;
+use arith::{self,Curve};
+use secp256r1::{Public,Secret,PARAMS};
+use hd::{self,DerivationPath,HARDENED,CHAIN_CODE_SIZE,FINGERPRINT_SIZE,MIN_SEED_SIZE,MAX_SEED_SIZE};
+use error::Error;
+
+
+/// size of curve.
+const BYTES: usize = 32;
+
+
+/// hmac key used to derive a master key from a seed.
+const SEED_KEY: &[u8] = b"Nist256p1 seed";
+
+
+/// an extended secret key.  its `Debug` output is redacted, since anyone
+/// who sees it holds every key derived from it.
+#[derive(PartialEq,Eq)]
+pub struct XPrv {
+    secret: Secret,
+    public: Public,
+    chain_code: [u8;CHAIN_CODE_SIZE],
+    depth: u8,
+    parent_fingerprint: [u8;FINGERPRINT_SIZE],
+    child_number: u32,
+}
+
+impl XPrv {
+    /// derive the master key from `seed`, which must be 16 to 64 bytes long
+    /// (or `Error::InvalidDerivation` results).
+    pub fn from_seed(seed: &[u8]) -> Result<Self,Error> {
+        if seed.len() < MIN_SEED_SIZE || seed.len() > MAX_SEED_SIZE {
+            return Err(Error::InvalidDerivation);
+        }
+        let curve = Curve::new(&PARAMS);
+        let mut i = hd::hmac(SEED_KEY,&[seed]);
+        // an invalid key is retried with the previous output as the seed.
+        while !curve.is_scalar(&arith::from_be(&i[..BYTES])) {
+            i = hd::hmac(SEED_KEY,&[&i]);
+        }
+        let rslt = XPrv::from_parts(&i,0,[0u8;FINGERPRINT_SIZE],0);
+        arith::wipe_bytes(&mut i);
+        rslt
+    }
+
+    /// derive the child key at `index` (hardened if at least `HARDENED`).
+    /// a child beyond depth 255 gives `Error::InvalidDerivation`.
+    pub fn derive_child(&self, index: u32) -> Result<Self,Error> {
+        let depth = self.depth.checked_add(1).ok_or(Error::InvalidDerivation)?;
+        let mut data = [0u8;BYTES+1];
+        if index >= HARDENED {
+            data[1..].copy_from_slice(self.secret.as_ref());
+        } else {
+            data.copy_from_slice(self.public.as_ref());
+        }
+        let mut i = hd::hmac(&self.chain_code,&[&data,&index.to_be_bytes()]);
+        arith::wipe_bytes(&mut data);
+        // the child's secret is `k + IL mod n`.
+        let mut il = [0u8;BYTES];
+        let child = loop {
+            il.copy_from_slice(&i[..BYTES]);
+            if let Ok(child) = self.secret.tweak_add(&il) { break child; }
+            // an invalid key is retried with `0x01 || IR` as the data.
+            let retry = hd::hmac(&self.chain_code,&[&[0x01],&i[BYTES..],&index.to_be_bytes()]);
+            arith::wipe_bytes(&mut i);
+            i = retry;
+        };
+        i[..BYTES].copy_from_slice(child.as_ref());
+        let rslt = XPrv::from_parts(&i,depth,self.fingerprint(),index);
+        arith::wipe_bytes(&mut il);
+        arith::wipe_bytes(&mut i);
+        rslt
+    }
+
+    /// derive the key at `path`, relative to this one.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self,Error> {
+        let mut bytes = self.to_bytes();
+        let key = XPrv::from_parts(&bytes,self.depth,self.parent_fingerprint,self.child_number);
+        arith::wipe_bytes(&mut bytes);
+        let mut key = key?;
+        for index in path.indices() {
+            key = key.derive_child(*index)?;
+        }
+        Ok(key)
+    }
+
+    /// the extended public key, which can derive this key's non-hardened
+    /// public children.
+    pub fn to_xpub(&self) -> XPub {
+        XPub {
+            public: Public::from(public_bytes(&self.public)),
+            chain_code: self.chain_code,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+        }
+    }
+
+    /// the secret key.
+    pub fn secret(&self) -> &Secret { &self.secret }
+
+    /// the public key.
+    pub fn public(&self) -> &Public { &self.public }
+
+    /// the chain code.
+    pub fn chain_code(&self) -> &[u8;CHAIN_CODE_SIZE] { &self.chain_code }
+
+    /// the number of derivations from the master key (which has depth 0).
+    pub fn depth(&self) -> u8 { self.depth }
+
+    /// the index this key was derived at (0 for the master key).
+    pub fn child_number(&self) -> u32 { self.child_number }
+
+    /// the fingerprint of the parent key (all zeros for the master key).
+    pub fn parent_fingerprint(&self) -> [u8;FINGERPRINT_SIZE] { self.parent_fingerprint }
+
+    /// this key's fingerprint, computed as in bip-32.
+    pub fn fingerprint(&self) -> [u8;FINGERPRINT_SIZE] { hd::fingerprint(self.public.as_ref()) }
+
+    /// build a key from `i`, the secret followed by the chain code.
+    fn from_parts(i: &[u8], depth: u8, parent_fingerprint: [u8;FINGERPRINT_SIZE], child_number: u32) -> Result<Self,Error> {
+        let mut bytes = [0u8;BYTES];
+        bytes.copy_from_slice(&i[..BYTES]);
+        let secret = Secret::try_from_bytes(&bytes);
+        arith::wipe_bytes(&mut bytes);
+        let secret = secret.map_err(|_| Error::InvalidDerivation)?;
+        let public = secret.public()?;
+        let mut chain_code = [0u8;CHAIN_CODE_SIZE];
+        chain_code.copy_from_slice(&i[BYTES..]);
+        Ok(XPrv { secret, public, chain_code, depth, parent_fingerprint, child_number })
+    }
+
+    /// the secret followed by the chain code.
+    fn to_bytes(&self) -> [u8;BYTES+CHAIN_CODE_SIZE] {
+        let mut bytes = [0u8;BYTES+CHAIN_CODE_SIZE];
+        bytes[..BYTES].copy_from_slice(self.secret.as_ref());
+        bytes[BYTES..].copy_from_slice(&self.chain_code);
+        bytes
+    }
+}
+
+impl fmt::Debug for XPrv {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("XPrv([redacted])") }
+}
+
+impl Drop for XPrv {
+    fn drop(&mut self) { arith::wipe_bytes(&mut self.chain_code); }
+}
+
+
+/// an extended public key.
+#[derive(Debug,PartialEq,Eq)]
+pub struct XPub {
+    public: Public,
+    chain_code: [u8;CHAIN_CODE_SIZE],
+    depth: u8,
+    parent_fingerprint: [u8;FINGERPRINT_SIZE],
+    child_number: u32,
+}
+
+impl XPub {
+    /// derive the public child key at `index`, which must not be hardened
+    /// (or `Error::InvalidDerivation` results, as it does for a child
+    /// beyond depth 255).
+    pub fn derive_child(&self, index: u32) -> Result<Self,Error> {
+        if index >= HARDENED {
+            return Err(Error::InvalidDerivation);
+        }
+        let depth = self.depth.checked_add(1).ok_or(Error::InvalidDerivation)?;
+        self.public.validate()?;
+        let mut i = hd::hmac(&self.chain_code,&[self.public.as_ref(),&index.to_be_bytes()]);
+        // the child is `K + IL*G`, retried as for secret derivation.
+        let mut il = [0u8;BYTES];
+        let public = loop {
+            il.copy_from_slice(&i[..BYTES]);
+            if let Ok(public) = self.public.tweak_add(&il) { break public; }
+            i = hd::hmac(&self.chain_code,&[&[0x01],&i[BYTES..],&index.to_be_bytes()]);
+        };
+        let mut chain_code = [0u8;CHAIN_CODE_SIZE];
+        chain_code.copy_from_slice(&i[BYTES..]);
+        Ok(XPub { public, chain_code, depth, parent_fingerprint: self.fingerprint(), child_number: index })
+    }
+
+    /// derive the key at `path`, relative to this one.  a path with a
+    /// hardened index yields `Error::InvalidDerivation`.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self,Error> {
+        let mut key = XPub {
+            public: Public::from(public_bytes(&self.public)),
+            chain_code: self.chain_code,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+        };
+        for index in path.indices() {
+            key = key.derive_child(*index)?;
+        }
+        Ok(key)
+    }
+
+    /// the public key.
+    pub fn public(&self) -> &Public { &self.public }
+
+    /// the chain code.
+    pub fn chain_code(&self) -> &[u8;CHAIN_CODE_SIZE] { &self.chain_code }
+
+    /// the number of derivations from the master key (which has depth 0).
+    pub fn depth(&self) -> u8 { self.depth }
+
+    /// the index this key was derived at (0 for the master key).
+    pub fn child_number(&self) -> u32 { self.child_number }
+
+    /// the fingerprint of the parent key (all zeros for the master key).
+    pub fn parent_fingerprint(&self) -> [u8;FINGERPRINT_SIZE] { self.parent_fingerprint }
+
+    /// this key's fingerprint, computed as in bip-32.
+    pub fn fingerprint(&self) -> [u8;FINGERPRINT_SIZE] { hd::fingerprint(self.public.as_ref()) }
+}
+
+
+/// copy out the bytes of a compressed public key.
+fn public_bytes(public: &Public) -> [u8;BYTES+1] {
+    let mut bytes = [0u8;BYTES+1];
+    bytes.copy_from_slice(public.as_ref());
+    bytes
+}
+
+
+#[cfg(test)]
+mod tests {
+    use slip10::XPrv;
+    use hd::{DerivationPath,HARDENED};
+    use error::Error;
+    use hex::unhex;
+
+    // slip-0010 test vectors for nist256p1, as (path, parent fingerprint,
+    // chain code, secret, public).
+    const VECTOR_1: &[(&str,&str,&str,&str,&str)] = &[
+        ("m","00000000",
+         "beeb672fe4621673f722f38529c07392fecaa61015c80c34f29ce8b41b3cb6ea",
+         "612091aaa12e22dd2abef664f8a01a82cae99ad7441b7ef8110424915c268bc2",
+         "0266874dc6ade47b3ecd096745ca09bcd29638dd52c2c12117b11ed3e458cfa9e8"),
+        ("m/0'","be6105b5",
+         "3460cea53e6a6bb5fb391eeef3237ffd8724bf0a40e94943c98b83825342ee11",
+         "6939694369114c67917a182c59ddb8cafc3004e63ca5d3b84403ba8613debc0c",
+         "0384610f5ecffe8fda089363a41f56a5c7ffc1d81b59a612d0d649b2d22355590c"),
+        ("m/0'/1","9b02312f",
+         "4187afff1aafa8445010097fb99d23aee9f599450c7bd140b6826ac22ba21d0c",
+         "284e9d38d07d21e4e281b645089a94f4cf5a5a81369acf151a1c3a57f18b2129",
+         "03526c63f8d0b4bbbf9c80df553fe66742df4676b241dabefdef67733e070f6844"),
+        ("m/0'/1/2'","b98005c1",
+         "98c7514f562e64e74170cc3cf304ee1ce54d6b6da4f880f313e8204c2a185318",
+         "694596e8a54f252c960eb771a3c41e7e32496d03b954aeb90f61635b8e092aa7",
+         "0359cf160040778a4b14c5f4d7b76e327ccc8c4a6086dd9451b7482b5a4972dda0"),
+        ("m/0'/1/2'/2","0e9f3274",
+         "ba96f776a5c3907d7fd48bde5620ee374d4acfd540378476019eab70790c63a0",
+         "5996c37fd3dd2679039b23ed6f70b506c6b56b3cb5e424681fb0fa64caf82aaa",
+         "029f871f4cb9e1c97f9f4de9ccd0d4a2f2a171110c61178f84430062230833ff20"),
+        ("m/0'/1/2'/2/1000000000","8b2b5c4b",
+         "b9b7b82d326bb9cb5b5b121066feea4eb93d5241103c9e7a18aad40f1dde8059",
+         "21c4f269ef0a5fd1badf47eeacebeeaa3de22eb8e5b0adcd0f27dd99d34d0119",
+         "02216cd26d31147f72427a453c443ed2cde8a1e53c9cc44e5ddf739725413fe3f4"),
+    ];
+
+    fn check(key: &XPrv, vector: &(&str,&str,&str,&str,&str)) {
+        let &(_, parent, chain_code, secret, public) = vector;
+        assert_eq!(key.parent_fingerprint().to_vec(),unhex(parent));
+        assert_eq!(key.chain_code().to_vec(),unhex(chain_code));
+        assert_eq!(key.secret().as_ref(),&unhex(secret)[..]);
+        assert_eq!(key.public().as_ref(),&unhex(public)[..]);
+    }
+
+    #[test]
+    fn derive_ok() {
+        let master = XPrv::from_seed(&unhex("000102030405060708090a0b0c0d0e0f")).unwrap();
+        for vector in VECTOR_1 {
+            let path: DerivationPath = vector.0.parse().unwrap();
+            check(&master.derive_path(&path).unwrap(),vector);
+        }
+        // public derivation agrees with private derivation for the
+        // non-hardened steps.
+        let parent = master.derive_path(&"m/0'/1/2'".parse().unwrap()).unwrap();
+        let child = parent.to_xpub().derive_path(&"m/2/1000000000".parse().unwrap()).unwrap();
+        assert_eq!(child.public().as_ref(),&unhex(VECTOR_1[5].4)[..]);
+        assert_eq!(child.parent_fingerprint().to_vec(),unhex(VECTOR_1[5].1));
+        assert_eq!(format!("{:?}",master),"XPrv([redacted])");
+    }
+
+    #[test]
+    fn derive_retry() {
+        // from the slip-0010 vectors exercising retries: `m/28578'` lands
+        // on an invalid key in child derivation, & this seed does in master
+        // key derivation.
+        let master = XPrv::from_seed(&unhex("000102030405060708090a0b0c0d0e0f")).unwrap();
+        let key = master.derive_path(&"m/28578'/33941".parse().unwrap()).unwrap();
+        check(&key,&("","3e2b7bc6",
+            "9e87fe95031f14736774cd82f25fd885065cb7c358c1edf813c72af535e83071",
+            "092154eed4af83e078ff9b84322015aefe5769e31270f62c3f66c33888335f3a",
+            "0235bfee614c0d5b2cae260000bb1d0d84b270099ad790022c1ae0b2e782efe120"));
+        let hardened = master.derive_child(28578 | HARDENED).unwrap();
+        assert_eq!(hardened.to_xpub().derive_child(33941).unwrap().public(),key.public());
+        let master = XPrv::from_seed(&unhex("a7305bc8df8d0951f0cb224c0e95d7707cbdf2c6ce7e8d481fec69c7ff5e9446")).unwrap();
+        check(&master,&("","00000000",
+            "7762f9729fed06121fd13f326884c82f59aa95c57ac492ce8c9654e60efd130c",
+            "3b8c18469a4634517d6d0b65448f8e6c62091b45540a1743c5846be55d47d88f",
+            "0383619fadcde31063d8c5cb00dbfe1713f3e6fa169d8541a798752a1c1ca0cb20"));
+    }
+
+    #[test]
+    fn derive_err() {
+        let master = XPrv::from_seed(&unhex("000102030405060708090a0b0c0d0e0f")).unwrap();
+        assert_eq!(master.to_xpub().derive_child(HARDENED).err(),Some(Error::InvalidDerivation));
+        assert_eq!(XPrv::from_seed(&[0u8;15]).err(),Some(Error::InvalidDerivation));
+        assert_eq!(XPrv::from_seed(&[0u8;65]).err(),Some(Error::InvalidDerivation));
+    }
+}
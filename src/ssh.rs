@@ -0,0 +1,60 @@
+//! openssh public key lines (rfc4253 §6.6 & rfc5656 §3.1), as found in
+//! `authorized_keys` & `*.pub` files.
+use base64;
+use error::Error;
+
+
+/// encode the sec1 `point` on the curve with ssh name `curve` (e.g.
+/// `nistp256`), followed by `comment` if it's non-empty.
+pub fn encode(curve: &str, point: &[u8], comment: &str) -> String {
+    let name = format!("ecdsa-sha2-{}",curve);
+    let mut blob = Vec::new();
+    write_string(name.as_bytes(),&mut blob);
+    write_string(curve.as_bytes(),&mut blob);
+    write_string(point,&mut blob);
+    let mut out = format!("{} {}",name,base64::encode(&blob,base64::STANDARD,true));
+    if !comment.is_empty() {
+        out.push(' ');
+        out.push_str(comment);
+    }
+    out
+}
+
+
+/// decode a line holding a key on the curve with ssh name `curve`, returning
+/// the sec1 point.  keys of any other type yield `Error::InvalidKey`, while
+/// a missing or malformed key blob yields `Error::MalformedEncoding`.  the
+/// comment (if any) is ignored.
+pub fn decode(curve: &str, line: &str) -> Result<Vec<u8>,Error> {
+    let name = format!("ecdsa-sha2-{}",curve);
+    let mut fields = line.split_whitespace();
+    if fields.next().ok_or(Error::MalformedEncoding)? != name {
+        return Err(Error::InvalidKey);
+    }
+    let blob = base64::decode(fields.next().ok_or(Error::MalformedEncoding)?,base64::STANDARD,true)?;
+    let mut rest = &blob[..];
+    if read_string(&mut rest)? != name.as_bytes() || read_string(&mut rest)? != curve.as_bytes() {
+        return Err(Error::InvalidKey);
+    }
+    let point = read_string(&mut rest)?;
+    if !rest.is_empty() { return Err(Error::MalformedEncoding); }
+    Ok(point.to_vec())
+}
+
+
+/// append a length-prefixed ssh `string`.
+fn write_string(bytes: &[u8], out: &mut Vec<u8>) {
+    out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+
+/// read a length-prefixed ssh `string` from the front of `buf`.
+fn read_string<'a>(buf: &mut &'a [u8]) -> Result<&'a [u8],Error> {
+    if buf.len() < 4 { return Err(Error::MalformedEncoding); }
+    let len = u32::from_be_bytes([buf[0],buf[1],buf[2],buf[3]]) as usize;
+    if buf.len() - 4 < len { return Err(Error::MalformedEncoding); }
+    let string = &buf[4..4+len];
+    *buf = &buf[4+len..];
+    Ok(string)
+}
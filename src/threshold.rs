@@ -0,0 +1,590 @@
+//! threshold (t-of-n) schnorr signatures over `secp256k1`, producing
+//! ordinary bip-340 signatures which verify against the group's x-only key
+//! with `schnorr::Public::verify`.
+//!
+//! keys are set up without a trusted dealer by pedersen's distributed key
+//! generation (feldman commitments, with a proof of possession of each
+//! participant's constant term as in frost), & messages are signed with the
+//! two-round frost protocol of komlo & goldberg.  participants are numbered
+//! `1..=n`.  every message passed between rounds is a plain serializable
+//! struct, but the `Share`s of key generation must travel over private,
+//! authenticated channels.
+//!
+//! a signer's `Nonces` are the hiding & binding nonces behind the
+//! `NonceCommitment` it publishes in the first round, & `KeyShare::sign`
+//! consumes them.  each signature share is linear in the nonces & the key
+//! share, so shares made under one pair of nonces let whoever collects them
+//! solve for the signer's key share.  `Nonces` therefore has no `Clone` or
+//! serde impls, & every signing session needs a fresh `commit`.
+//!
+//! threshold ecdsa isn't provided: it needs paillier encryption & range
+//! proofs, well beyond what the crate's arithmetic supports.
+use arith::{self,Curve,Limbs,Modulus,Point,MAX_LIMBS};
+use secp256k1::{self,PARAMS};
+use schnorr;
+use error::Error;
+use random;
+
+
+/// size of curve.
+const BYTES: usize = 32;
+
+
+/// tag of the hash challenging a proof of possession.
+const POP_TAG: &str = "easy-ecc/threshold/pop";
+
+/// tag of the hash of a signing session's nonce commitments.
+const COMMITMENTS_TAG: &str = "easy-ecc/threshold/commitments";
+
+/// tag of the hash deriving each signer's binding factor.
+const BINDING_TAG: &str = "easy-ecc/threshold/binding";
+
+
+/// a participant's broadcast in the first round of key generation: feldman
+/// commitments to the coefficients of its secret polynomial, & a schnorr
+/// proof of possession of the constant term.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct Commitment {
+    /// index of the participant.
+    pub index: u16,
+    /// the points `a_k G` for each coefficient `a_k`, constant term first.
+    pub coefficients: Vec<secp256k1::Public>,
+    /// nonce point of the proof of possession.
+    pub proof_r: secp256k1::Public,
+    /// big-endian response scalar of the proof of possession.
+    pub proof_z: [u8;BYTES],
+}
+
+impl Commitment {
+    /// check the proof of possession, yielding `Error::InvalidKey` if it
+    /// doesn't verify or if any commitment isn't a valid curve point.
+    pub fn verify(&self) -> Result<(),Error> {
+        let curve = Curve::new(&PARAMS);
+        let constant = self.coefficients.first().ok_or(Error::InvalidKey)?;
+        for point in &self.coefficients[1..] {
+            schnorr::decode_point(&curve,point)?;
+        }
+        let z = arith::from_be(&self.proof_z);
+        if !curve.fq.contains(&z) {
+            return Err(Error::InvalidKey);
+        }
+        let c = pop_challenge(self.index,constant,&self.proof_r);
+        let lhs = curve.mul(&curve.generator(),&z);
+        let rhs = curve.add(&schnorr::decode_point(&curve,&self.proof_r)?,&curve.mul(&schnorr::decode_point(&curve,constant)?,&c));
+        if curve.equal(&lhs,&rhs) { Ok(()) } else { Err(Error::InvalidKey) }
+    }
+}
+
+
+/// a secret share, sent privately from one participant to another in the
+/// second round of key generation: the sender's polynomial evaluated at the
+/// recipient's index.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct Share {
+    /// index of the sending participant.
+    pub from: u16,
+    /// index of the receiving participant.
+    pub to: u16,
+    /// the big-endian share scalar.
+    pub value: secp256k1::Secret,
+}
+
+impl Share {
+    /// check the share against its sender's commitment, yielding
+    /// `Error::InvalidKey` if they don't match.
+    pub fn verify(&self, commitment: &Commitment) -> Result<(),Error> {
+        if commitment.index != self.from {
+            return Err(Error::InvalidParticipants);
+        }
+        let curve = Curve::new(&PARAMS);
+        let mut s = arith::from_be(self.value.as_ref());
+        let rslt = if curve.fq.contains(&s) {
+            let expected = evaluate_points(&curve,&commitment.coefficients,self.to)?;
+            if curve.equal(&curve.mul(&curve.generator(),&s),&expected) { Ok(()) } else { Err(Error::InvalidKey) }
+        } else {
+            Err(Error::InvalidKey)
+        };
+        arith::wipe(&mut s);
+        rslt
+    }
+}
+
+
+/// a participant's private state during key generation (the coefficients of
+/// its secret polynomial, which are wiped on drop).
+pub struct KeyGen {
+    index: u16,
+    participants: u16,
+    coefficients: Vec<Limbs>,
+}
+
+impl KeyGen {
+    /// begin key generation as participant `index` of `participants`, any
+    /// `threshold` of whom will be able to sign.  returns the state to keep
+    /// & the commitment to broadcast to every other participant.
+    pub fn new(index: u16, threshold: u16, participants: u16) -> Result<(KeyGen,Commitment),Error> {
+        if threshold == 0 || threshold > participants || index == 0 || index > participants {
+            return Err(Error::InvalidParticipants);
+        }
+        let curve = Curve::new(&PARAMS);
+        let mut keygen = KeyGen { index, participants, coefficients: Vec::with_capacity(threshold as usize) };
+        for _ in 0..threshold {
+            keygen.coefficients.push(random::scalar(&curve.fq)?);
+        }
+        let coefficients = keygen.coefficients.iter()
+            .map(|a| schnorr::encode_point(&curve,&curve.mul(&curve.generator(),a)))
+            .collect::<Result<Vec<_>,_>>()?;
+        let mut k = random::scalar(&curve.fq)?;
+        let proof_r = schnorr::encode_point(&curve,&curve.mul(&curve.generator(),&k))?;
+        let c = pop_challenge(index,&coefficients[0],&proof_r);
+        let mut z = curve.fq.add(&k,&curve.fq.mul_reduced(&c,&keygen.coefficients[0]));
+        let mut proof_z = [0u8;BYTES];
+        arith::to_be(&z,&mut proof_z);
+        arith::wipe(&mut k);
+        arith::wipe(&mut z);
+        Ok((keygen,Commitment { index, coefficients, proof_r, proof_z }))
+    }
+
+    /// the secret share for participant `to`, to be sent to them privately.
+    pub fn share(&self, to: u16) -> Result<Share,Error> {
+        if to == 0 || to > self.participants {
+            return Err(Error::InvalidParticipants);
+        }
+        Ok(Share { from: self.index, to, value: to_secret(&mut self.evaluate(to)) })
+    }
+
+    /// complete key generation, given the commitments broadcast by every
+    /// participant (this one included) & the shares sent to this participant
+    /// by every other.  a missing or repeated participant yields
+    /// `Error::InvalidParticipants`, & a commitment or share which doesn't
+    /// verify yields `Error::InvalidKey` (`Commitment::verify` &
+    /// `Share::verify` pin it on the culprit).
+    pub fn finish(self, commitments: &[Commitment], shares: &[Share]) -> Result<KeyShare,Error> {
+        let curve = Curve::new(&PARAMS);
+        let n = self.participants as usize;
+        if commitments.len() != n || shares.len() != n - 1 {
+            return Err(Error::InvalidParticipants);
+        }
+        let mut group = vec![curve.identity();self.coefficients.len()];
+        for i in 1..=self.participants {
+            let commitment = commitments.iter().find(|c| c.index == i).ok_or(Error::InvalidParticipants)?;
+            if commitment.coefficients.len() != group.len() {
+                return Err(Error::InvalidKey);
+            }
+            commitment.verify()?;
+            if i != self.index {
+                let share = shares.iter().find(|s| s.from == i && s.to == self.index).ok_or(Error::InvalidParticipants)?;
+                share.verify(commitment)?;
+            }
+            for (sum,point) in group.iter_mut().zip(&commitment.coefficients) {
+                *sum = curve.add(sum,&schnorr::decode_point(&curve,point)?);
+            }
+        }
+        let group = GroupKey {
+            commitments: group.iter().map(|point| schnorr::encode_point(&curve,point)).collect::<Result<Vec<_>,_>>()?,
+        };
+        let mut secret = self.evaluate(self.index);
+        for share in shares {
+            secret = curve.fq.add(&secret,&arith::from_be(share.value.as_ref()));
+        }
+        Ok(KeyShare { index: self.index, secret: to_secret(&mut secret), group })
+    }
+
+    /// evaluate the secret polynomial at `x`, by horner's rule.
+    fn evaluate(&self, x: u16) -> Limbs {
+        let fq = Curve::new(&PARAMS).fq;
+        let x = scalar(x);
+        self.coefficients.iter().rev()
+            .fold([0;MAX_LIMBS],|acc,a| fq.add(&fq.mul_reduced(&acc,&x),a))
+    }
+}
+
+impl Drop for KeyGen {
+    fn drop(&mut self) {
+        for a in self.coefficients.iter_mut() {
+            arith::wipe(a);
+        }
+    }
+}
+
+
+/// the public outcome of key generation, shared by every participant: the
+/// sum of all participants' coefficient commitments.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct GroupKey {
+    /// the summed commitments, constant term (the group's public point)
+    /// first.
+    pub commitments: Vec<secp256k1::Public>,
+}
+
+impl GroupKey {
+    /// number of participants needed to sign.
+    pub fn threshold(&self) -> usize { self.commitments.len() }
+
+    /// the group's x-only bip-340 public key.
+    pub fn public(&self) -> Result<schnorr::Public,Error> {
+        let curve = Curve::new(&PARAMS);
+        let (x,_) = self.point(&curve)?;
+        let mut public = [0u8;BYTES];
+        arith::to_be(&x,&mut public);
+        Ok(schnorr::Public::from(public))
+    }
+
+    /// the public counterpart of participant `index`'s signing share.
+    pub fn verification_share(&self, index: u16) -> Result<secp256k1::Public,Error> {
+        let curve = Curve::new(&PARAMS);
+        schnorr::encode_point(&curve,&evaluate_points(&curve,&self.commitments,index)?)
+    }
+
+    /// check one signer's share of a session's signature, so that a bad
+    /// share can be pinned on its signer when `aggregate` fails.  a share
+    /// which doesn't verify yields `Error::InvalidSignature`.
+    pub fn verify_share(&self, msg: &[u8], commitments: &[NonceCommitment], share: &SignatureShare) -> Result<(),Error> {
+        let session = Session::new(self,msg,commitments)?;
+        let (curve, fq) = (&session.curve, &session.curve.fq);
+        let pos = commitments.iter().position(|c| c.index == share.index).ok_or(Error::InvalidParticipants)?;
+        let mut z = arith::from_be(&share.z);
+        if !fq.contains(&z) {
+            return Err(Error::MalformedSignature);
+        }
+        let nonce = curve.add(&schnorr::decode_point(curve,&commitments[pos].hiding)?,&curve.mul(&schnorr::decode_point(curve,&commitments[pos].binding)?,&session.rho[pos]));
+        let mut factor = fq.mul_reduced(&lagrange(fq,share.index,commitments),&session.c);
+        if session.odd_y { factor = fq.neg(&factor); }
+        // with an odd `R` the signer negated its nonce, so check `-zG`.
+        if session.odd_r {
+            z = fq.neg(&z);
+            factor = fq.neg(&factor);
+        }
+        let lhs = curve.mul(&curve.generator(),&z);
+        let rhs = curve.add(&nonce,&curve.mul(&evaluate_points(curve,&self.commitments,share.index)?,&factor));
+        if curve.equal(&lhs,&rhs) { Ok(()) } else { Err(Error::InvalidSignature) }
+    }
+
+    /// the affine group point.
+    fn point(&self, curve: &Curve) -> Result<(Limbs,Limbs),Error> {
+        let constant = self.commitments.first().ok_or(Error::InvalidKey)?;
+        curve.to_affine(&schnorr::decode_point(curve,constant)?).ok_or(Error::InvalidKey)
+    }
+}
+
+
+/// a participant's signing share, the outcome of key generation.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct KeyShare {
+    index: u16,
+    secret: secp256k1::Secret,
+    group: GroupKey,
+}
+
+impl KeyShare {
+    /// index of the participant.
+    pub fn index(&self) -> u16 { self.index }
+
+    /// the participant's secret signing share.
+    pub fn secret(&self) -> &secp256k1::Secret { &self.secret }
+
+    /// the group's public key material.
+    pub fn group(&self) -> &GroupKey { &self.group }
+
+    /// begin a signing session: draw fresh nonces, returning them along with
+    /// the commitment to send to the other signers.
+    pub fn commit(&self) -> Result<(Nonces,NonceCommitment),Error> {
+        let curve = Curve::new(&PARAMS);
+        let mut nonces = Nonces { index: self.index, hiding: [0;MAX_LIMBS], binding: [0;MAX_LIMBS] };
+        nonces.hiding = random::scalar(&curve.fq)?;
+        nonces.binding = random::scalar(&curve.fq)?;
+        let commitment = NonceCommitment {
+            index: self.index,
+            hiding: schnorr::encode_point(&curve,&curve.mul(&curve.generator(),&nonces.hiding))?,
+            binding: schnorr::encode_point(&curve,&curve.mul(&curve.generator(),&nonces.binding))?,
+        };
+        Ok((nonces,commitment))
+    }
+
+    /// produce this participant's share of the signature over `msg`, given
+    /// its nonces for the session & the nonce commitments of every signer
+    /// (this one included) in ascending index order.  signers out of order,
+    /// fewer than the threshold, or a set which doesn't include this
+    /// participant with the commitment matching `nonces`, yield
+    /// `Error::InvalidParticipants`.
+    pub fn sign(&self, nonces: Nonces, msg: &[u8], commitments: &[NonceCommitment]) -> Result<SignatureShare,Error> {
+        let session = Session::new(&self.group,msg,commitments)?;
+        let (curve, fq) = (&session.curve, &session.curve.fq);
+        let pos = commitments.iter().position(|c| c.index == self.index).ok_or(Error::InvalidParticipants)?;
+        let hiding = schnorr::encode_point(curve,&curve.mul(&curve.generator(),&nonces.hiding))?;
+        let binding = schnorr::encode_point(curve,&curve.mul(&curve.generator(),&nonces.binding))?;
+        if nonces.index != self.index || hiding != commitments[pos].hiding || binding != commitments[pos].binding {
+            return Err(Error::InvalidParticipants);
+        }
+        let mut k = fq.add(&nonces.hiding,&fq.mul_reduced(&nonces.binding,&session.rho[pos]));
+        if session.odd_r { k = fq.neg(&k); }
+        let mut s = arith::from_be(self.secret.as_ref());
+        if session.odd_y { s = fq.neg(&s); }
+        let mut ls = fq.mul_reduced(&fq.mul_reduced(&lagrange(fq,self.index,commitments),&session.c),&s);
+        let z = fq.add(&k,&ls);
+        let mut share = SignatureShare { index: self.index, z: [0u8;BYTES] };
+        arith::to_be(&z,&mut share.z);
+        arith::wipe(&mut k);
+        arith::wipe(&mut s);
+        arith::wipe(&mut ls);
+        Ok(share)
+    }
+}
+
+
+/// a signer's secret nonces for one signing session.  they're consumed by
+/// `KeyShare::sign` (& wiped on drop), since signing twice with the same
+/// nonces would leak the signing share.
+pub struct Nonces {
+    index: u16,
+    hiding: Limbs,
+    binding: Limbs,
+}
+
+impl Drop for Nonces {
+    fn drop(&mut self) {
+        arith::wipe(&mut self.hiding);
+        arith::wipe(&mut self.binding);
+    }
+}
+
+
+/// a signer's broadcast in the first round of signing: commitments to its
+/// hiding & binding nonces.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct NonceCommitment {
+    /// index of the signer.
+    pub index: u16,
+    /// the point `d G` for the hiding nonce `d`.
+    pub hiding: secp256k1::Public,
+    /// the point `e G` for the binding nonce `e`.
+    pub binding: secp256k1::Public,
+}
+
+
+/// a signer's output in the second round of signing.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct SignatureShare {
+    /// index of the signer.
+    pub index: u16,
+    /// the big-endian response scalar.
+    pub z: [u8;BYTES],
+}
+
+
+/// combine the signature shares of a session (in the same order as its
+/// nonce commitments) into a bip-340 signature, which is verified against
+/// the group key before being returned.  a signature which doesn't verify
+/// yields `Error::InvalidSignature` (`GroupKey::verify_share` identifies the
+/// bad shares).
+pub fn aggregate(group: &GroupKey, msg: &[u8], commitments: &[NonceCommitment], shares: &[SignatureShare]) -> Result<schnorr::Signature,Error> {
+    let session = Session::new(group,msg,commitments)?;
+    let fq = &session.curve.fq;
+    if shares.len() != commitments.len() || shares.iter().zip(commitments).any(|(s,c)| s.index != c.index) {
+        return Err(Error::InvalidParticipants);
+    }
+    let mut z = [0;MAX_LIMBS];
+    for share in shares {
+        let zi = arith::from_be(&share.z);
+        if !fq.contains(&zi) {
+            return Err(Error::MalformedSignature);
+        }
+        z = fq.add(&z,&zi);
+    }
+    let mut raw = [0u8;BYTES*2];
+    raw[..BYTES].copy_from_slice(&session.rx);
+    arith::to_be(&z,&mut raw[BYTES..]);
+    let sig = schnorr::Signature::from(raw);
+    group.public()?.verify(msg,&sig)?;
+    Ok(sig)
+}
+
+
+/// the values of a signing session shared by every signer: the binding
+/// factor of each, the group nonce & the bip-340 challenge.
+struct Session {
+    curve: Curve,
+    rho: Vec<Limbs>,
+    rx: [u8;BYTES],
+    odd_r: bool,
+    odd_y: bool,
+    c: Limbs,
+}
+
+impl Session {
+    fn new(group: &GroupKey, msg: &[u8], commitments: &[NonceCommitment]) -> Result<Self,Error> {
+        if commitments.len() < group.threshold() || commitments.iter().any(|c| c.index == 0)
+            || commitments.windows(2).any(|pair| pair[0].index >= pair[1].index) {
+            return Err(Error::InvalidParticipants);
+        }
+        let curve = Curve::new(&PARAMS);
+        let (x,y) = group.point(&curve)?;
+        let mut px = [0u8;BYTES];
+        arith::to_be(&x,&mut px);
+        let mut encoded = Vec::new();
+        for c in commitments {
+            encoded.extend_from_slice(&c.index.to_be_bytes());
+            encoded.extend_from_slice(c.hiding.as_ref());
+            encoded.extend_from_slice(c.binding.as_ref());
+        }
+        let list = schnorr::tagged_hash(COMMITMENTS_TAG,&[&encoded]);
+        let mut rho = Vec::with_capacity(commitments.len());
+        let mut r = curve.identity();
+        for c in commitments {
+            let hash = schnorr::tagged_hash(BINDING_TAG,&[&c.index.to_be_bytes(),&px,&list,msg]);
+            let factor = curve.fq.reduce(&arith::from_be(&hash));
+            let nonce = curve.add(&schnorr::decode_point(&curve,&c.hiding)?,&curve.mul(&schnorr::decode_point(&curve,&c.binding)?,&factor));
+            r = curve.add(&r,&nonce);
+            rho.push(factor);
+        }
+        let (rx, ry) = curve.to_affine(&r).ok_or(Error::InvalidSignature)?;
+        let mut session = Session { curve, rho, rx: [0u8;BYTES], odd_r: ry[0] & 1 == 1, odd_y: y[0] & 1 == 1, c: [0;MAX_LIMBS] };
+        arith::to_be(&rx,&mut session.rx);
+        session.c = schnorr::challenge(&session.curve,&session.rx,&px,msg);
+        Ok(session)
+    }
+}
+
+
+/// the lagrange coefficient of signer `index` at zero, over the signers of
+/// `commitments`.
+fn lagrange(fq: &Modulus, index: u16, commitments: &[NonceCommitment]) -> Limbs {
+    let i = scalar(index);
+    let (mut num, mut den) = (scalar(1), scalar(1));
+    for j in commitments.iter().map(|c| c.index).filter(|&j| j != index) {
+        let j = scalar(j);
+        num = fq.mul_reduced(&num,&j);
+        den = fq.mul_reduced(&den,&fq.sub(&j,&i));
+    }
+    fq.mul_reduced(&num,&fq.canonical(&fq.inv(&fq.mont_form(&den))))
+}
+
+
+/// evaluate the committed polynomial at `x`, by horner's rule in the
+/// exponent.
+fn evaluate_points(curve: &Curve, coefficients: &[secp256k1::Public], x: u16) -> Result<Point,Error> {
+    let x = scalar(x);
+    coefficients.iter().rev().try_fold(curve.identity(),|acc,point| {
+        Ok(curve.add(&curve.mul(&acc,&x),&schnorr::decode_point(curve,point)?))
+    })
+}
+
+
+/// the challenge of a proof of possession.
+fn pop_challenge(index: u16, constant: &secp256k1::Public, r: &secp256k1::Public) -> Limbs {
+    let fq = Curve::new(&PARAMS).fq;
+    fq.reduce(&arith::from_be(&schnorr::tagged_hash(POP_TAG,&[&index.to_be_bytes(),constant.as_ref(),r.as_ref()])))
+}
+
+
+/// a small integer as a scalar.
+fn scalar(x: u16) -> Limbs {
+    let mut limbs = [0;MAX_LIMBS];
+    limbs[0] = x as u64;
+    limbs
+}
+
+
+/// move a scalar into a secret key, wiping the original.
+fn to_secret(s: &mut Limbs) -> secp256k1::Secret {
+    let mut bytes = [0u8;BYTES];
+    arith::to_be(s,&mut bytes);
+    let secret = secp256k1::Secret::from(bytes);
+    arith::wipe(s);
+    arith::wipe_bytes(&mut bytes);
+    secret
+}
+
+
+#[cfg(test)]
+mod tests {
+    use threshold::{KeyGen,KeyShare,Share,aggregate};
+    use secp256k1;
+    use error::Error;
+
+    /// run key generation for `n` participants with threshold `t`.
+    fn dkg(t: u16, n: u16) -> Vec<KeyShare> {
+        let (states, commitments): (Vec<_>,Vec<_>) = (1..=n).map(|i| KeyGen::new(i,t,n).unwrap()).unzip();
+        let shares: Vec<Vec<Share>> = states.iter()
+            .map(|state| (1..=n).filter(|&j| j != state.index).map(|j| state.share(j).unwrap()).collect())
+            .collect();
+        states.into_iter().map(|state| {
+            let index = state.index;
+            let received: Vec<_> = shares.iter().flat_map(|sent| sent.iter()).filter(|s| s.to == index)
+                .map(|s| Share { from: s.from, to: s.to, value: secp256k1::Secret::from_slice(s.value.as_ref()).unwrap() })
+                .collect();
+            state.finish(&commitments,&received).unwrap()
+        }).collect()
+    }
+
+    /// sign `msg` with the given subset of key shares.
+    fn sign(keys: &[&KeyShare], msg: &[u8]) -> Result<::schnorr::Signature,Error> {
+        let (nonces, commitments): (Vec<_>,Vec<_>) = keys.iter().map(|key| key.commit().unwrap()).unzip();
+        let shares: Vec<_> = keys.iter().zip(nonces)
+            .map(|(key,nonces)| key.sign(nonces,msg,&commitments).unwrap())
+            .collect();
+        for share in &shares {
+            keys[0].group().verify_share(msg,&commitments,share).unwrap();
+        }
+        aggregate(keys[0].group(),msg,&commitments,&shares)
+    }
+
+    #[test]
+    fn threshold_ok() {
+        let keys = dkg(2,3);
+        let public = keys[0].group().public().unwrap();
+        assert!(keys.iter().all(|key| key.group() == keys[0].group()));
+        for key in &keys {
+            assert_eq!(key.secret().public().unwrap(),key.group().verification_share(key.index()).unwrap());
+        }
+        for subset in &[[0,1],[0,2],[1,2]] {
+            let sig = sign(&[&keys[subset[0]],&keys[subset[1]]],b"hello").unwrap();
+            public.verify(b"hello",&sig).unwrap();
+            assert_eq!(public.verify(b"hellO",&sig),Err(Error::InvalidSignature));
+        }
+        let sig = sign(&keys.iter().collect::<Vec<_>>(),b"all").unwrap();
+        public.verify(b"all",&sig).unwrap();
+    }
+
+    #[test]
+    fn threshold_err() {
+        assert_eq!(KeyGen::new(1,3,2).err(),Some(Error::InvalidParticipants));
+        assert_eq!(KeyGen::new(0,1,2).err(),Some(Error::InvalidParticipants));
+        let keys = dkg(2,3);
+        // too few signers.
+        let (nonces, commitment) = keys[0].commit().unwrap();
+        assert_eq!(keys[0].sign(nonces,b"msg",&[commitment]).err(),Some(Error::InvalidParticipants));
+        // a tampered signature share is caught & attributed.
+        let (n0, c0) = keys[0].commit().unwrap();
+        let (n1, c1) = keys[1].commit().unwrap();
+        let commitments = [c0,c1];
+        let good = keys[0].sign(n0,b"msg",&commitments).unwrap();
+        let mut bad = keys[1].sign(n1,b"msg",&commitments).unwrap();
+        bad.z[31] ^= 0x01;
+        let group = keys[0].group();
+        group.verify_share(b"msg",&commitments,&good).unwrap();
+        assert_eq!(group.verify_share(b"msg",&commitments,&bad),Err(Error::InvalidSignature));
+        assert_eq!(aggregate(group,b"msg",&commitments,&[good,bad]).err(),Some(Error::InvalidSignature));
+    }
+
+    #[test]
+    fn dkg_err() {
+        let (a, ca) = KeyGen::new(1,2,2).unwrap();
+        let (b, cb) = KeyGen::new(2,2,2).unwrap();
+        let mut share = b.share(1).unwrap();
+        share.verify(&cb).unwrap();
+        assert_eq!(share.verify(&ca),Err(Error::InvalidParticipants));
+        share.value = secp256k1::Secret::from([0x01;32]);
+        assert_eq!(share.verify(&cb),Err(Error::InvalidKey));
+        let mut forged = KeyGen::new(2,2,2).unwrap().1;
+        forged.proof_z = cb.proof_z;
+        assert_eq!(forged.verify(),Err(Error::InvalidKey));
+        assert_eq!(a.finish(&[ca,cb],&[share]).err(),Some(Error::InvalidKey));
+    }
+}
@@ -0,0 +1,132 @@
+//! elliptic curve verifiable random functions as described in rfc9381, for
+//! the suite `ECVRF-P256-SHA256-TAI` (hashing to the curve by
+//! try-and-increment, & rfc6979 nonces).  only built with the `secp256r1`
+//! feature.
+use arith::{self,Curve,Limbs,Point};
+use rfc6979::Nonces;
+use sha2::{Sha256,Digest};
+use error::Error;
+
+
+/// size of a curve scalar or field element.
+const BYTES: usize = 32;
+
+/// size of a compressed point.
+const PT_LEN: usize = BYTES + 1;
+
+/// size of the challenge scalar.
+const C_LEN: usize = 16;
+
+/// size of a proof (`Gamma || c || s`).
+pub const PROOF_LEN: usize = PT_LEN + C_LEN + BYTES;
+
+/// size of an output.
+pub const OUTPUT_LEN: usize = BYTES;
+
+/// suite string of `ECVRF-P256-SHA256-TAI`.
+const SUITE: u8 = 0x01;
+
+
+/// prove the output for `alpha` under secret scalar `x` (whose compressed
+/// public point is `pk`), writing the proof into `proof`.
+pub fn prove(curve: &Curve, x: &Limbs, pk: &[u8], alpha: &[u8], proof: &mut [u8]) -> Result<[u8;OUTPUT_LEN],Error> {
+    let fq = &curve.fq;
+    let h = encode_to_curve(curve,pk,alpha)?;
+    let hs = encode(curve,&h)?;
+    let gamma = curve.mul(&h,x);
+    let gs = encode(curve,&gamma)?;
+    let mut k = Nonces::new(fq,x,&Sha256::digest(hs)).next().expect("nonces are endless");
+    let u = encode(curve,&curve.mul(&curve.generator(),&k))?;
+    let v = encode(curve,&curve.mul(&h,&k))?;
+    let c = challenge(&[pk,&hs,&gs,&u,&v]);
+    let mut cx = fq.mul_reduced(&c,x);
+    let s = fq.add(&k,&cx);
+    arith::wipe(&mut k);
+    arith::wipe(&mut cx);
+    proof[..PT_LEN].copy_from_slice(&gs);
+    arith::to_be(&c,&mut proof[PT_LEN..PT_LEN+C_LEN]);
+    arith::to_be(&s,&mut proof[PT_LEN+C_LEN..]);
+    Ok(proof_to_hash(&gs))
+}
+
+
+/// verify a proof for `alpha` under public point `y` (with compressed
+/// encoding `pk`), returning the output.  a proof which doesn't verify
+/// yields `Error::InvalidSignature`, & one which can't be decoded yields
+/// `Error::MalformedSignature`.
+pub fn verify(curve: &Curve, y: &Point, pk: &[u8], alpha: &[u8], proof: &[u8]) -> Result<[u8;OUTPUT_LEN],Error> {
+    let fq = &curve.fq;
+    let gs = &proof[..PT_LEN];
+    let gamma = curve.decompress(gs).ok_or(Error::MalformedSignature)?;
+    let c = arith::from_be(&proof[PT_LEN..PT_LEN+C_LEN]);
+    let s = arith::from_be(&proof[PT_LEN+C_LEN..]);
+    if !fq.contains(&s) {
+        return Err(Error::MalformedSignature);
+    }
+    let h = encode_to_curve(curve,pk,alpha)?;
+    let minus_c = fq.neg(&c);
+    // `U = sB - cY` & `V = sH - c Gamma`.
+    let u = curve.add(&curve.mul(&curve.generator(),&s),&curve.mul(y,&minus_c));
+    let v = curve.add(&curve.mul(&h,&s),&curve.mul(&gamma,&minus_c));
+    let (u, v) = match (encode(curve,&u), encode(curve,&v)) {
+        (Ok(u), Ok(v)) => (u, v),
+        _ => return Err(Error::InvalidSignature),
+    };
+    if challenge(&[pk,&encode(curve,&h)?,gs,&u,&v]) == c {
+        Ok(proof_to_hash(gs))
+    } else {
+        Err(Error::InvalidSignature)
+    }
+}
+
+
+/// hash `alpha` to a curve point by try-and-increment (rfc9381 §5.4.1.1),
+/// treating each candidate hash as the x-coordinate of an even-y point.
+fn encode_to_curve(curve: &Curve, pk: &[u8], alpha: &[u8]) -> Result<Point,Error> {
+    for ctr in 0..=255u8 {
+        let mut hasher = Sha256::new();
+        hasher.update([SUITE,0x01]);
+        hasher.update(pk);
+        hasher.update(alpha);
+        hasher.update([ctr,0x00]);
+        let mut candidate = [0x02;PT_LEN];
+        candidate[1..].copy_from_slice(&hasher.finalize());
+        if let Some(point) = curve.decompress(&candidate) {
+            return Ok(point);
+        }
+    }
+    Err(Error::RetriesExhausted)
+}
+
+
+/// the challenge (rfc9381 §5.4.3): the first `C_LEN` bytes of a hash of
+/// the given points.
+fn challenge(points: &[&[u8]]) -> Limbs {
+    let mut hasher = Sha256::new();
+    hasher.update([SUITE,0x02]);
+    for point in points {
+        hasher.update(point);
+    }
+    hasher.update([0x00]);
+    arith::from_be(&hasher.finalize()[..C_LEN])
+}
+
+
+/// the output for a proof's `Gamma` (the curve's cofactor is one).
+fn proof_to_hash(gamma: &[u8]) -> [u8;OUTPUT_LEN] {
+    let mut hasher = Sha256::new();
+    hasher.update([SUITE,0x03]);
+    hasher.update(gamma);
+    hasher.update([0x00]);
+    let mut out = [0u8;OUTPUT_LEN];
+    out.copy_from_slice(&hasher.finalize());
+    out
+}
+
+
+/// compress a point, which mustn't be the point at infinity.
+fn encode(curve: &Curve, point: &Point) -> Result<[u8;PT_LEN],Error> {
+    let mut out = [0u8;PT_LEN];
+    if curve.compress(point,&mut out) { Ok(out) } else { Err(Error::InvalidKey) }
+}
+
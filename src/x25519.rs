@@ -0,0 +1,398 @@
+//! types and functions for diffie-hellman key agreement over curve25519, as
+//! described in rfc7748.
+//!
+//! `easy-ecc` only implements short weierstrass curves, so this module is
+//! backed by the crate's portable field arithmetic.  unlike the ecdsa curve
+//! modules, keys & shared secrets are little-endian, & every 32-byte string
+//! is a valid secret key.
+use arith::{self,Limbs,Modulus,MAX_LIMBS};
+use ecies;
+use error::Error;
+use hkdf;
+use random;
+
+
+/// size of curve.
+const BYTES: usize = 32;
+
+
+/// size of a serialized public key.
+pub const PUBLIC_KEY_SIZE: usize = BYTES;
+
+/// size of a serialized secret key.
+pub const SECRET_KEY_SIZE: usize = BYTES;
+
+/// size of a diffie-hellman shared secret.
+pub const SHARED_SECRET_SIZE: usize = BYTES;
+
+/// bytes added to a plaintext by `Public::seal`.
+pub const SEAL_OVERHEAD: usize = 1 + PUBLIC_KEY_SIZE + ecies::TAG_LEN;
+
+
+/// the field prime `2^255 - 19`.
+const P: &[u64] = &[0xFFFFFFFFFFFFFFED, 0xFFFFFFFFFFFFFFFF, 0xFFFFFFFFFFFFFFFF, 0x7FFFFFFFFFFFFFFF];
+
+
+/// `(A - 2) / 4` for the montgomery curve coefficient `A = 486662`.
+const A24: u64 = 121665;
+
+
+/// u-coordinate of the base point.
+const BASE_U: u64 = 9;
+
+
+/// a public x25519 key (a little-endian u-coordinate).
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Hash,PartialOrd,Ord)]
+pub struct Public([u8;BYTES]);
+impl_newtype_bytearray!(Public,BYTES);
+impl_serde_bytearray!(Public,BYTES);
+impl_hex_fmt!(Public);
+impl_from_slice!(Public,PUBLIC_KEY_SIZE);
+impl_bytes!(Public,PUBLIC_KEY_SIZE);
+
+impl Default for Public {
+    fn default() -> Self { Public([0u8;BYTES]) }
+}
+
+impl Public {
+    /// seal `plaintext` to this key, in the manner of libsodium's
+    /// `crypto_box_seal` (though not interoperable with it): the sender is
+    /// anonymous, & only the holder of the matching secret key can open the
+    /// box (with `Secret::open_sealed`).  boxes use the ecies wire format of
+    /// the ecdsa curve modules, with the ephemeral key as a raw u-coordinate.
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>,Error> {
+        seal_with(self,&KeyPair::generate()?,plaintext)
+    }
+}
+
+
+/// a secret x25519 key (an unclamped little-endian scalar).
+pub struct Secret([u8;BYTES]);
+impl_newtype_bytearray!(Secret,BYTES);
+impl_redacted_fmt!(Secret);
+impl_ct_eq!(Secret);
+impl_serde_bytearray!(Secret,BYTES);
+impl_from_slice!(Secret,SECRET_KEY_SIZE);
+
+impl Default for Secret {
+    fn default() -> Self { Secret([0u8;BYTES]) }
+}
+
+impl Secret {
+    /// generate a new random secret key.
+    pub fn generate() -> Result<Self,Error> {
+        let mut secret = Secret::default();
+        random::fill(&mut secret.0)?;
+        Ok(secret)
+    }
+
+    /// the raw secret key bytes, for the rare cases (such as export) where
+    /// they must be shown.  never log them.
+    pub fn expose_secret(&self) -> &[u8;BYTES] { &self.0 }
+
+    /// derive the public key corresponding to this secret key.
+    pub fn public(&self) -> Public {
+        let mut base = [0u8;BYTES];
+        base[0] = BASE_U as u8;
+        Public(ladder(&self.0,&base))
+    }
+
+    /// open a box sealed to this key's public key.  a box which is
+    /// truncated, of an unknown version, or carries a low-order ephemeral
+    /// key yields `Error::MalformedEncoding`; one which was tampered with or
+    /// sealed to another key yields `Error::DecryptionFailed`.
+    pub fn open_sealed(&self, sealed: &[u8]) -> Result<Vec<u8>,Error> {
+        let ephemeral = Public::from_slice(ecies::ephemeral(sealed,PUBLIC_KEY_SIZE)?)?;
+        let shared = ecdh(&ephemeral,self).map_err(|_| Error::MalformedEncoding)?;
+        ecies::open(ecies::INFO,&self.public().0,&shared.0,sealed,PUBLIC_KEY_SIZE)
+    }
+}
+
+impl_zeroize!(Secret);
+
+
+/// a shared secret derived via x25519.
+///
+/// the raw bytes are only reachable through `expose_secret`, & are never
+/// printed or serialized; use `derive_key` to obtain keys.
+pub struct SharedSecret([u8;BYTES]);
+impl_secret_bytes!(SharedSecret,BYTES);
+impl_from_slice!(SharedSecret,SHARED_SECRET_SIZE);
+
+impl Default for SharedSecret {
+    fn default() -> Self { SharedSecret([0u8;BYTES]) }
+}
+
+impl SharedSecret {
+    /// derive a `len`-byte symmetric key with hkdf-sha256 (rfc5869).  the
+    /// shared secret itself isn't uniformly random, so it should never be
+    /// used as a key directly.  asking for more than 8160 bytes yields
+    /// `Error::WrongLength`.
+    pub fn derive_key(&self, salt: &[u8], info: &[u8], len: usize) -> Result<Vec<u8>,Error> {
+        let mut key = vec![0u8;len];
+        hkdf::derive(salt,&self.0,info,&mut key)?;
+        Ok(key)
+    }
+}
+
+impl_zeroize!(SharedSecret);
+
+
+/// a public/secret x25519 keypair.
+#[derive(Debug,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize,Deserialize))]
+pub struct KeyPair {
+    public: Public,
+    secret: Secret,
+}
+impl_envelope_key!("x25519",Public,Secret,KeyPair);
+
+impl KeyPair {
+    /// generate a new random keypair.
+    pub fn generate() -> Result<Self,Error> {
+        let secret = Secret::generate()?;
+        Ok(KeyPair { public: secret.public(), secret })
+    }
+
+    /// get the public half of this keypair.
+    pub fn public(&self) -> &Public { &self.public }
+
+    /// get the secret half of this keypair.
+    pub fn secret(&self) -> &Secret { &self.secret }
+
+    /// open a box sealed to this keypair's public key.
+    pub fn open_sealed(&self, sealed: &[u8]) -> Result<Vec<u8>,Error> {
+        self.secret.open_sealed(sealed)
+    }
+}
+
+
+/// a single-use x25519 secret key.  its only capability is
+/// `diffie_hellman`, which consumes it, & it can't be serialized or cloned,
+/// so an ephemeral key can't be reused or leaked by accident.
+pub struct EphemeralSecret {
+    secret: Secret,
+    public: Public,
+}
+
+impl EphemeralSecret {
+    /// generate a new random ephemeral key.
+    pub fn generate() -> Result<Self,Error> {
+        let KeyPair { public, secret } = KeyPair::generate()?;
+        Ok(EphemeralSecret { secret, public })
+    }
+
+    /// get the public key to send to the other party.
+    pub fn public(&self) -> &Public { &self.public }
+
+    /// derive the shared secret with `public`, consuming this key.  fails as
+    /// `ecdh` does.
+    pub fn diffie_hellman(self, public: &Public) -> Result<SharedSecret,Error> {
+        ecdh(public,&self.secret)
+    }
+}
+
+
+/// generate a new x25519 keypair into `public` & `secret`, which are left
+/// untouched on failure.
+#[deprecated(note = "use `KeyPair::generate` or `Secret::generate`, which return the new keys")]
+pub fn keygen(public: &mut Public, secret: &mut Secret) -> Result<(),Error> {
+    let pair = KeyPair::generate()?;
+    *public = pair.public;
+    *secret = pair.secret;
+    Ok(())
+}
+
+
+/// derive a shared secret from a public key and a secret key.  fails with
+/// `Error::InvalidKey` if `public` is a low-order point (which would yield
+/// an all-zero secret).
+pub fn ecdh(public: &Public, secret: &Secret) -> Result<SharedSecret,Error> {
+    let shared = SharedSecret(ladder(&secret.0,&public.0));
+    if shared.0.iter().fold(0, |acc,byte| acc | byte) == 0 {
+        return Err(Error::InvalidKey);
+    }
+    Ok(shared)
+}
+
+
+/// derive a 32-byte symmetric key (as for aes-256) from a public key and a
+/// secret key, by `ecdh` followed by hkdf-sha256 with an empty salt & the
+/// given `info`.
+pub fn ecdh_hkdf(public: &Public, secret: &Secret, info: &[u8]) -> Result<[u8;hkdf::KEY_LEN],Error> {
+    let mut key = [0u8;hkdf::KEY_LEN];
+    hkdf::derive(&[],&ecdh(public,secret)?.0,info,&mut key)?;
+    Ok(key)
+}
+
+
+/// seal `plaintext` to `recipient` under the given ephemeral keypair,
+/// which mustn't be used again.
+fn seal_with(recipient: &Public, ephemeral: &KeyPair, plaintext: &[u8]) -> Result<Vec<u8>,Error> {
+    let shared = ecdh(recipient,&ephemeral.secret)?;
+    ecies::seal(ecies::INFO,&ephemeral.public.0,&recipient.0,&shared.0,plaintext)
+}
+
+
+/// the x25519 function of rfc7748 §5: multiply u-coordinate `u` by the
+/// clamped scalar `k`.
+fn ladder(k: &[u8;BYTES], u: &[u8;BYTES]) -> [u8;BYTES] {
+    let f = Modulus::new(P);
+    let mut scalar = *k;
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+    // the top bit of `u` is masked & non-canonical values are accepted.
+    let mut ubytes = *u;
+    ubytes[31] &= 127;
+    ubytes.reverse();
+    let x1 = f.mont_form(&f.reduce(&arith::from_be(&ubytes)));
+    let mut a24 = [0u64;MAX_LIMBS];
+    a24[0] = A24;
+    let a24 = f.mont_form(&a24);
+    let (mut x2, mut z2) = (f.one(), [0u64;MAX_LIMBS]);
+    let (mut x3, mut z3) = (x1, f.one());
+    let mut swap = 0u64;
+    for t in (0..255).rev() {
+        let bit = ((scalar[t / 8] >> (t % 8)) & 1) as u64;
+        swap ^= bit;
+        cswap(&mut x2,&mut x3,swap.wrapping_neg());
+        cswap(&mut z2,&mut z3,swap.wrapping_neg());
+        swap = bit;
+        let a = f.add(&x2,&z2);
+        let aa = f.mul(&a,&a);
+        let b = f.sub(&x2,&z2);
+        let bb = f.mul(&b,&b);
+        let e = f.sub(&aa,&bb);
+        let c = f.add(&x3,&z3);
+        let d = f.sub(&x3,&z3);
+        let da = f.mul(&d,&a);
+        let cb = f.mul(&c,&b);
+        let sum = f.add(&da,&cb);
+        x3 = f.mul(&sum,&sum);
+        let diff = f.sub(&da,&cb);
+        z3 = f.mul(&x1,&f.mul(&diff,&diff));
+        x2 = f.mul(&aa,&bb);
+        z2 = f.mul(&e,&f.add(&aa,&f.mul(&a24,&e)));
+    }
+    cswap(&mut x2,&mut x3,swap.wrapping_neg());
+    cswap(&mut z2,&mut z3,swap.wrapping_neg());
+    let x = f.canonical(&f.mul(&x2,&f.inv(&z2)));
+    let mut out = [0u8;BYTES];
+    arith::to_be(&x,&mut out);
+    out.reverse();
+    arith::wipe_bytes(&mut scalar);
+    arith::wipe(&mut x2);
+    arith::wipe(&mut x3);
+    out
+}
+
+
+/// swap `a` & `b` if `mask` is all ones.
+fn cswap(a: &mut Limbs, b: &mut Limbs, mask: u64) {
+    let a0 = *a;
+    *a = arith::select(a,b,mask);
+    *b = arith::select(b,&a0,mask);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use x25519::{BYTES,SEAL_OVERHEAD,Public,Secret,SharedSecret,KeyPair,EphemeralSecret,ecdh,seal_with};
+    use error::Error;
+    use hex;
+
+    fn unhex(s: &str) -> [u8;BYTES] {
+        let mut out = [0u8;BYTES];
+        hex::decode(s,&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn keygen_ok() {
+        use x25519::keygen;
+        let mut public = Public::default();
+        let mut secret = Secret::default();
+        keygen(&mut public, &mut secret).unwrap();
+
+        assert!(secret != Secret::default());
+        assert_eq!(secret.public(),public);
+    }
+
+    #[test]
+    fn ecdh_ok() {
+        let (pair_a, pair_b) = (KeyPair::generate().unwrap(), KeyPair::generate().unwrap());
+        let shared_a = ecdh(pair_b.public(),pair_a.secret()).unwrap();
+        let shared_b = ecdh(pair_a.public(),pair_b.secret()).unwrap();
+        assert_eq!(shared_a,shared_b);
+    }
+
+    #[test]
+    fn rfc7748_ok() {
+        // rfc7748 §5.2.
+        let secret = Secret::from(unhex("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4"));
+        let public = Public::from(unhex("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c"));
+        assert_eq!(ecdh(&public,&secret).unwrap(),
+            SharedSecret::from(unhex("c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552")));
+        // rfc7748 §6.1.
+        let alice = Secret::from(unhex("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a"));
+        let bob = Secret::from(unhex("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb"));
+        assert_eq!(alice.public(),Public::from(unhex("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")));
+        assert_eq!(bob.public(),Public::from(unhex("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")));
+        let shared = SharedSecret::from(unhex("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742"));
+        assert_eq!(ecdh(&bob.public(),&alice).unwrap(),shared);
+        assert_eq!(ecdh(&alice.public(),&bob).unwrap(),shared);
+    }
+
+    #[test]
+    fn ecdh_err() {
+        // the identity encodes as u = 0, a low-order point.
+        let pair = KeyPair::generate().unwrap();
+        assert_eq!(ecdh(&Public::default(),pair.secret()),Err(Error::InvalidKey));
+    }
+
+    #[test]
+    fn seal_ok() {
+        let pair = KeyPair::generate().unwrap();
+        for msg in &[&b""[..],&b"config blob"[..]] {
+            let sealed = pair.public().seal(msg).unwrap();
+            assert_eq!(sealed.len(),msg.len() + SEAL_OVERHEAD);
+            assert_eq!(pair.open_sealed(&sealed).unwrap(),msg.to_vec());
+        }
+        // as printed by `vectors/x25519.py`, which seals with python's
+        // `cryptography` package.
+        let recipient = Secret::from([0x11;BYTES]);
+        let ephemeral = Secret::from([0x22;BYTES]);
+        let ephemeral = KeyPair { public: ephemeral.public(), secret: ephemeral };
+        let sealed = seal_with(&recipient.public(),&ephemeral,b"config blob").unwrap();
+        let expected = "010faa684ed28867b97f4a6a2dee5df8ce974e76b7018e3f22a1c4cf2678570f20e44743f5a50c630c2c46740e801ab9ba96d1b842f60f1f11380567";
+        assert_eq!(sealed.iter().map(|byte| format!("{:02x}",byte)).collect::<String>(),expected);
+        assert_eq!(recipient.open_sealed(&sealed).unwrap(),b"config blob".to_vec());
+    }
+
+    #[test]
+    fn seal_err() {
+        let pair = KeyPair::generate().unwrap();
+        let other = KeyPair::generate().unwrap();
+        let sealed = pair.public().seal(b"config blob").unwrap();
+        assert_eq!(other.open_sealed(&sealed),Err(Error::DecryptionFailed));
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 0x01;
+        assert_eq!(pair.open_sealed(&tampered),Err(Error::DecryptionFailed));
+        let mut low_order = sealed.clone();
+        for byte in low_order[1..1+BYTES].iter_mut() { *byte = 0; }
+        assert_eq!(pair.open_sealed(&low_order),Err(Error::MalformedEncoding));
+        assert_eq!(pair.open_sealed(&sealed[..SEAL_OVERHEAD - 1]),Err(Error::MalformedEncoding));
+        assert_eq!(Public::default().seal(b"config blob"),Err(Error::InvalidKey));
+    }
+
+    #[test]
+    fn ephemeral_ok() {
+        let pair = KeyPair::generate().unwrap();
+        let ephemeral = EphemeralSecret::generate().unwrap();
+        let theirs = ecdh(ephemeral.public(),pair.secret()).unwrap();
+        assert_eq!(ephemeral.diffie_hellman(pair.public()).unwrap(),theirs);
+        assert_eq!(EphemeralSecret::generate().unwrap().diffie_hellman(&Public::default()).err(),Some(Error::InvalidKey));
+    }
+}
@@ -0,0 +1,39 @@
+"""reproduce the `ecies` & multi-recipient envelope vectors in
+`src/secp256r1.rs` with python's `cryptography` package:
+`python3 vectors/ecies.py`."""
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import ec
+from cryptography.hazmat.primitives.ciphers.aead import AESGCM
+from cryptography.hazmat.primitives.kdf.hkdf import HKDF
+
+NONCE = bytes(12)
+
+
+def key(byte):
+    """the secret key `byte * 32`."""
+    return ec.derive_private_key(int.from_bytes(bytes([byte] * 32), "big"), ec.SECP256R1())
+
+
+def point(secret):
+    return secret.public_key().public_bytes(serialization.Encoding.X962, serialization.PublicFormat.CompressedPoint)
+
+
+def cipher(info, ephemeral, recipient):
+    """aes-256-gcm under hkdf-sha256 over the ecdh secret, with an empty salt
+    & `info || ephemeral || recipient` as the info."""
+    shared = ephemeral.exchange(ec.ECDH(), recipient.public_key())
+    return AESGCM(HKDF(hashes.SHA256(), 32, b"", info + point(ephemeral) + point(recipient)).derive(shared))
+
+
+ephemeral = key(0x22)
+header = b"\x01" + point(ephemeral)
+
+# `ecies_vector`: "hello ecies" to the key `0x11 * 32`.
+print("ecies:", (header + cipher(b"easy-ecc/ecies/v1", ephemeral, key(0x11)).encrypt(NONCE, b"hello ecies", header)).hex())
+
+# `ecies_multi_vector`: "hello envelope" under the content key `0x44 * 32`,
+# wrapped to the keys `0x11 * 32` & `0x33 * 32`.
+content_key = bytes([0x44] * 32)
+wrapped = b"".join(cipher(b"easy-ecc/ecies/v1/wrap", ephemeral, key(byte)).encrypt(NONCE, content_key, b"") for byte in (0x11, 0x33))
+body = AESGCM(content_key).encrypt(NONCE, b"hello envelope", header)
+print("envelope:", (header + (2).to_bytes(2, "big") + wrapped + body).hex())
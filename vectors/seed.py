@@ -0,0 +1,38 @@
+"""reproduce the `KeyPair::from_seed`, `KeyPair::derive_child` &
+`KeyPair::from_mnemonic` vectors in `src/secp256r1.rs` with python's
+`cryptography` package: `python3 vectors/seed.py`."""
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import ec
+from cryptography.hazmat.primitives.kdf.hkdf import HKDF
+
+N = 0xffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551
+
+
+def expand(ikm, info):
+    """rejection-sample a scalar from hkdf-sha256 over `ikm`, with an empty
+    salt & `info || counter` as the info."""
+    for counter in range(256):
+        okm = HKDF(hashes.SHA256(), 32, b"", info + bytes([counter])).derive(ikm)
+        k = int.from_bytes(okm, "big")
+        if 0 < k < N:
+            return k
+
+
+def show(name, k):
+    public = ec.derive_private_key(k, ec.SECP256R1()).public_key()
+    print(name)
+    print("  secret:", k.to_bytes(32, "big").hex())
+    print("  public:", public.public_bytes(serialization.Encoding.X962, serialization.PublicFormat.CompressedPoint).hex())
+    return k
+
+
+# `from_seed_ok`: the seed `0x11 * 32`.
+parent = show("from_seed", expand(bytes([0x11] * 32), b"easy-ecc/seed/secp256r1"))
+
+# `derive_child_ok`: that key's `"signing"` child.
+show("derive_child", expand(parent.to_bytes(32, "big"), b"easy-ecc/child/secp256r1/signing"))
+
+# `from_mnemonic_ok`: the official bip-39 seed of "abandon ... about" under
+# the passphrase `TREZOR`.
+seed = bytes.fromhex("c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04")
+show("from_mnemonic", expand(seed, b"easy-ecc/seed/secp256r1"))
@@ -0,0 +1,20 @@
+"""reproduce the x25519 sealed-box vector in `src/x25519.rs` with python's
+`cryptography` package: `python3 vectors/x25519.py`."""
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric.x25519 import X25519PrivateKey
+from cryptography.hazmat.primitives.ciphers.aead import AESGCM
+from cryptography.hazmat.primitives.kdf.hkdf import HKDF
+
+
+def raw(secret):
+    return secret.public_key().public_bytes(serialization.Encoding.Raw, serialization.PublicFormat.Raw)
+
+
+# `seal_ok`: "config blob" to the key `0x11 * 32`, under the ephemeral key
+# `0x22 * 32`.
+recipient = X25519PrivateKey.from_private_bytes(bytes([0x11] * 32))
+ephemeral = X25519PrivateKey.from_private_bytes(bytes([0x22] * 32))
+shared = ephemeral.exchange(recipient.public_key())
+key = HKDF(hashes.SHA256(), 32, b"", b"easy-ecc/ecies/v1" + raw(ephemeral) + raw(recipient)).derive(shared)
+header = b"\x01" + raw(ephemeral)
+print("sealed:", (header + AESGCM(key).encrypt(bytes(12), b"config blob", header)).hex())